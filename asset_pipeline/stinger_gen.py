@@ -0,0 +1,77 @@
+# Synthesize the short gameplay stingers (wall bump and level win) as WAV files
+
+# Run with:
+# python asset_pipeline/stinger_gen.py
+
+import math
+import random
+import struct
+import wave
+
+# --- CONFIGURATION ---
+SAMPLE_RATE = 44100
+OUTPUT_DIR = "assets/audio/sound_effects"
+
+
+def envelope(t: float, duration: float, attack: float = 0.005):
+    """A quick fade in, then an exponential fade out."""
+    fade_in = min(t / attack, 1.0)
+    return fade_in * math.exp(-5.0 * t / duration)
+
+
+def bump():
+    """A dull thud: a falling low tone with a little noise."""
+    duration = 0.18
+    rng = random.Random(1)
+    samples = []
+    for i in range(int(duration * SAMPLE_RATE)):
+        t = i / SAMPLE_RATE
+        frequency = 140.0 - 200.0 * t
+        tone = math.sin(2.0 * math.pi * frequency * t)
+        noise = rng.uniform(-1.0, 1.0) * 0.3
+        samples.append((tone + noise) * envelope(t, duration) * 0.6)
+    return samples
+
+
+def win():
+    """A rising major arpeggio."""
+    notes = [523.25, 659.25, 783.99, 1046.50]  # C5 E5 G5 C6
+    note_duration = 0.12
+    tail = 0.5
+    length = note_duration * (len(notes) - 1) + tail
+    samples = [0.0] * int(length * SAMPLE_RATE)
+    for n, frequency in enumerate(notes):
+        start = int(n * note_duration * SAMPLE_RATE)
+        duration = tail if n == len(notes) - 1 else note_duration * 2.0
+        for i in range(int(duration * SAMPLE_RATE)):
+            if start + i >= len(samples):
+                break
+            t = i / SAMPLE_RATE
+            # A square-ish tone from a few odd harmonics.
+            tone = sum(
+                math.sin(2.0 * math.pi * frequency * k * t) / k for k in (1, 3, 5)
+            )
+            samples[start + i] += tone * envelope(t, duration) * 0.25
+    return samples
+
+
+def write_wav(path: str, samples: list[float]):
+    with wave.open(path, "wb") as f:
+        f.setnchannels(1)
+        f.setsampwidth(2)
+        f.setframerate(SAMPLE_RATE)
+        f.writeframes(
+            b"".join(
+                struct.pack("<h", int(max(-1.0, min(1.0, s)) * 32767)) for s in samples
+            )
+        )
+    print(f"Wrote {path}")
+
+
+def main():
+    write_wav(f"{OUTPUT_DIR}/bump.wav", bump())
+    write_wav(f"{OUTPUT_DIR}/win.wav", win())
+
+
+if __name__ == "__main__":
+    main()
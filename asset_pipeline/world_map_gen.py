@@ -1,4 +1,4 @@
-# Generate and save to file the matrix representing static world content from CSV files
+# Generate a level file representing static world content from CSV files
 
 # Run with:
 # python asset_pipeline/world_map_gen.py
@@ -6,25 +6,40 @@
 import csv
 
 # --- CONFIGURATION ---
+# Tiled tile ids to level file symbols (see the legend in the level file)
 GROUND_MAP = {
-    1: "GroundType::Grass",
-    3: "GroundType::DirtH",
-    4: "GroundType::DirtV",
+    1: "g",  # Grass
+    3: "-",  # DirtH
+    4: "|",  # DirtV
 }
 
 OBSTRUCTION_MAP = {
-    -1: "ObstructionType::None",
-    7: "ObstructionType::WallV",
-    6: "ObstructionType::WallH",
-    15: "ObstructionType::Tower",
-    3: "ObstructionType::Rock1",
-    4: "ObstructionType::Rock2",
-    5: "ObstructionType::Rock3",
+    -1: ".",  # None
+    7: "|",  # WallV
+    6: "-",  # WallH
+    15: "+",  # Tower
+    3: "1",  # Rock1
+    4: "2",  # Rock2
+    5: "3",  # Rock3
 }
 
 GROUND_CSV = "/Users/nathan/art/TLoAsh/Tiled/trial_Ground.csv"
 OBSTRUCTION_CSV = "/Users/nathan/art/TLoAsh/Tiled/trial_Walls.csv"
-OUTPUT_RS = "/Users/nathan/personal/bevy_maze/src/gameplay/environment/world_map_array.rs"
+OUTPUT_LEVEL = "/Users/nathan/personal/bevy_maze/assets/levels/trial.level.ron"
+LEVEL_NAME = "Trial"
+LEVEL_MUSIC = "audio/music/Fluffing A Duck.ogg"
+
+# Markers aren't drawn in Tiled, so they are written out from here.
+MARKERS = [
+    "PlayerStart(x: 0.5, y: 9.0)",
+    "FinishZone(x: 10, y: 1, width: 1, height: 2)",
+]
+
+LEGEND = (
+    "// Ground legend:      g = Grass, - = DirtH, | = DirtV\n"
+    "// Obstruction legend: . = None, | = WallV, - = WallH, + = Tower, 1 2 3 = Rock1 Rock2 Rock3\n"
+    "// Marker positions count tiles from the top left of the map.\n"
+)
 
 
 def read_csv(path: str):
@@ -32,24 +47,17 @@ def read_csv(path: str):
         return [[int(x) for x in row] for row in csv.reader(f)]
 
 
-def make_tile_matrix(ground_matrix: list[list[int]], obstruction_matrix: list[list[int]]):
-    tiles: list[list[str]] = []
-    for gy, row in enumerate(ground_matrix):
-        tile_row: list[str] = []
-        for gx, gval in enumerate(row):
-            oval = obstruction_matrix[gy][gx]
-            gstr = GROUND_MAP.get(gval, "GroundType::Grass")
-            ostr = OBSTRUCTION_MAP.get(oval, "ObstructionType::None")
-            tile_row.append(f"Tile {{ ground: {gstr}, obstruction: {ostr} }}")
-        tiles.append(tile_row)
-    return tiles
+def make_layer(matrix: list[list[int]], symbols: dict[int, str], default: str):
+    return ["".join(symbols.get(val, default) for val in row) for row in matrix]
+
+
+def format_ron_list(name: str, items: list[str]):
+    lines = "".join(f"        {item},\n" for item in items)
+    return f"    {name}: [\n{lines}    ],\n"
 
 
-def format_rust_matrix(tile_matrix: list[list[str]]):
-    rows: list[str] = []
-    for row in tile_matrix:
-        rows.append("    [" + ", ".join(row) + "]")
-    return "[\n" + ",\n".join(rows) + "\n];"
+def format_ron_layer(name: str, rows: list[str]):
+    return format_ron_list(name, [f'"{row}"' for row in rows])
 
 
 def main():
@@ -61,25 +69,21 @@ def main():
     ):
         raise ValueError("CSV matrices must be the same size.")
 
-    tiles = make_tile_matrix(ground, obstruction)
-    rust_matrix = format_rust_matrix(tiles)
-
-    rust_output = (
-        "// Auto-generated tile map\n"
-        "use super::{GroundType, ObstructionType, Tile};\n\n"
-        "pub const TILE_MAP: [[Tile; "
-        + str(len(ground))
-        + "]; "
-        + str(len(ground[0]))
-        + "] = "
-        + rust_matrix
-        + "\n"
+    ron_output = (
+        LEGEND
+        + "(\n"
+        + f'    name: "{LEVEL_NAME}",\n'
+        + f'    music: Some("{LEVEL_MUSIC}"),\n'
+        + format_ron_layer("ground", make_layer(ground, GROUND_MAP, "g"))
+        + format_ron_layer("obstructions", make_layer(obstruction, OBSTRUCTION_MAP, "."))
+        + format_ron_list("markers", MARKERS)
+        + ")\n"
     )
 
-    with open(OUTPUT_RS, "w") as f:
-        f.write(rust_output)
+    with open(OUTPUT_LEVEL, "w") as f:
+        f.write(ron_output)
 
-    print(f"Wrote Rust matrix to {OUTPUT_RS}")
+    print(f"Wrote level to {OUTPUT_LEVEL}")
 
 
 if __name__ == "__main__":
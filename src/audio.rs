@@ -1,10 +1,9 @@
-use bevy::prelude::*;
+use bevy::{audio::Volume, prelude::*};
+
+use crate::settings::Settings;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(
-        Update,
-        apply_global_volume.run_if(resource_changed::<GlobalVolume>),
-    );
+    app.add_systems(Update, apply_volume);
 }
 
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
@@ -33,12 +32,40 @@ pub fn sound_effect(handle: Handle<AudioSource>) -> impl Bundle {
     (AudioPlayer(handle), PlaybackSettings::DESPAWN, SoundEffect)
 }
 
-/// [`GlobalVolume`] doesn't apply to already-running audio entities, so this system will update them.
-fn apply_global_volume(
+/// [`GlobalVolume`] doesn't apply to already-running audio entities, and the
+/// [`Music`] and [`SoundEffect`] volumes don't apply to anything by themselves,
+/// so this system sets the volume of new audio and updates the rest whenever
+/// the volume settings change.
+fn apply_volume(
     global_volume: Res<GlobalVolume>,
-    mut audio_query: Query<(&PlaybackSettings, &mut AudioSink)>,
+    settings: Res<Settings>,
+    mut audio_query: Query<(
+        &PlaybackSettings,
+        &mut AudioSink,
+        Has<Music>,
+        Has<SoundEffect>,
+    )>,
 ) {
-    for (playback, mut sink) in &mut audio_query {
-        sink.set_volume(global_volume.volume * playback.volume);
+    let volume_changed = global_volume.is_changed() || settings.is_changed();
+    for (playback, mut sink, is_music, is_sound_effect) in &mut audio_query {
+        if !volume_changed && !sink.is_added() {
+            continue;
+        }
+        let category_volume = if is_music {
+            category_volume(settings.music_volume, settings.music_muted)
+        } else if is_sound_effect {
+            category_volume(settings.sfx_volume, settings.sfx_muted)
+        } else {
+            Volume::Linear(1.0)
+        };
+        sink.set_volume(global_volume.volume * category_volume * playback.volume);
+    }
+}
+
+fn category_volume(volume: f32, muted: bool) -> Volume {
+    if muted {
+        Volume::SILENT
+    } else {
+        Volume::Linear(volume)
     }
 }
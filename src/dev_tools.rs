@@ -1,10 +1,14 @@
 //! Development tools for the game. This plugin is only enabled in dev builds.
 
-use bevy::{
-    dev_tools::states::log_transitions, input::common_conditions::input_just_pressed, prelude::*,
-};
+use std::path::Path;
+
+use bevy::{dev_tools::states::log_transitions, prelude::*};
 
-use crate::screens::Screen;
+use crate::{
+    gameplay::level::validate::lint_level_file,
+    input::{InputAction, action_just_pressed},
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Log `Screen` state transitions.
@@ -13,12 +17,43 @@ pub(super) fn plugin(app: &mut App) {
     // Toggle the debug overlay for UI.
     app.add_systems(
         Update,
-        toggle_debug_ui.run_if(input_just_pressed(TOGGLE_KEY)),
+        toggle_debug_ui.run_if(action_just_pressed(InputAction::ToggleDebug)),
     );
 }
 
-const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
-
 fn toggle_debug_ui(mut options: ResMut<UiDebugOptions>) {
     options.toggle();
 }
+
+/// Check the level files named on the command line instead of starting the
+/// game, if the first argument is `lint-levels`. Fails if any level can't be
+/// completed.
+pub(super) fn lint_levels_from_args() -> Option<AppExit> {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some("lint-levels") {
+        return None;
+    }
+
+    let mut failed = false;
+    for path in args {
+        match lint_level_file(Path::new(&path)) {
+            Ok(issues) if issues.is_empty() => println!("{path}: ok"),
+            Ok(issues) => {
+                for issue in issues {
+                    let severity = if issue.is_error() { "error" } else { "warning" };
+                    println!("{path}: {severity}: {issue}");
+                    failed |= issue.is_error();
+                }
+            }
+            Err(error) => {
+                println!("{path}: error: {error}");
+                failed = true;
+            }
+        }
+    }
+    Some(if failed {
+        AppExit::error()
+    } else {
+        AppExit::Success
+    })
+}
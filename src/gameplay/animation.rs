@@ -45,6 +45,24 @@ fn update_animation_movement(
             Some(PlayerAnimationState::Walking(Direction::West))
         } else if controller.intent.x > 0.0 && controller.intent.y == 0.0 {
             Some(PlayerAnimationState::Walking(Direction::East))
+        } else if matches!(animation.state, PlayerAnimationState::Idling(_)) {
+            // Diagonal input keeps the current walking direction, but an analog
+            // stick is rarely exactly on an axis, so start walking along the
+            // stronger one.
+            let intent = controller.intent;
+            Some(PlayerAnimationState::Walking(
+                if intent.x.abs() > intent.y.abs() {
+                    if intent.x < 0.0 {
+                        Direction::West
+                    } else {
+                        Direction::East
+                    }
+                } else if intent.y < 0.0 {
+                    Direction::North
+                } else {
+                    Direction::South
+                },
+            ))
         } else {
             None
         };
@@ -150,6 +168,13 @@ impl PlayerAnimation {
         self.timer.is_finished()
     }
 
+    /// Whether a foot came down this tick.
+    pub fn is_step(&self) -> bool {
+        matches!(self.state, PlayerAnimationState::Walking(_))
+            && self.changed()
+            && (self.frame == 0 || self.frame == 2)
+    }
+
     /// Return sprite index in the atlas.
     pub fn get_atlas_index(&self) -> usize {
         match &self.state {
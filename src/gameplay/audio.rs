@@ -0,0 +1,118 @@
+//! Music and sound effects for gameplay. Levels can choose their own music;
+//! see [`Level::music`].
+//!
+//! [`Level::music`]: crate::gameplay::level::Level::music
+
+use bevy::prelude::*;
+use rand::seq::IndexedRandom;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::{music, sound_effect},
+    gameplay::{
+        animation::PlayerAnimation,
+        level::{CurrentLevel, load_level},
+        maze::MazeProgress,
+        movement::WallBump,
+        player::Player,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<GameplayAudioAssets>();
+    app.add_systems(OnEnter(Screen::Gameplay), start_music.after(load_level));
+    app.add_systems(
+        Update,
+        (play_step_sounds, play_bump_sounds)
+            .run_if(resource_exists::<GameplayAudioAssets>)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(OnEnter(MazeProgress::Finish), play_win_sound);
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct GameplayAudioAssets {
+    /// For levels that don't choose their own music.
+    #[dependency]
+    music: Handle<AudioSource>,
+    #[dependency]
+    steps: Vec<Handle<AudioSource>>,
+    #[dependency]
+    bump: Handle<AudioSource>,
+    #[dependency]
+    win: Handle<AudioSource>,
+}
+
+impl FromWorld for GameplayAudioAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            music: assets.load("audio/music/Fluffing A Duck.ogg"),
+            steps: vec![
+                assets.load("audio/sound_effects/step1.ogg"),
+                assets.load("audio/sound_effects/step2.ogg"),
+                assets.load("audio/sound_effects/step3.ogg"),
+                assets.load("audio/sound_effects/step4.ogg"),
+            ],
+            bump: assets.load("audio/sound_effects/bump.wav"),
+            win: assets.load("audio/sound_effects/win.wav"),
+        }
+    }
+}
+
+fn start_music(
+    mut commands: Commands,
+    audio_assets: Res<GameplayAudioAssets>,
+    current_level: Res<CurrentLevel>,
+) {
+    let track = current_level
+        .0
+        .music
+        .clone()
+        .unwrap_or_else(|| audio_assets.music.clone());
+    commands.spawn((
+        Name::new("Gameplay Music"),
+        music(track),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+/// Play a random footstep whenever the player's walk animation puts a foot down.
+///
+/// Which footstep plays is deliberately left out of the [`MazeSeed`]: it can't
+/// change how a run plays out, and seeding it would tie the seed's random
+/// stream to how many steps the player takes.
+///
+/// [`MazeSeed`]: crate::gameplay::maze::seed::MazeSeed
+fn play_step_sounds(
+    mut commands: Commands,
+    audio_assets: Res<GameplayAudioAssets>,
+    animations: Query<&PlayerAnimation, With<Player>>,
+) {
+    for animation in &animations {
+        if animation.is_step()
+            && let Some(step) = audio_assets.steps.choose(&mut rand::rng())
+        {
+            commands.spawn(sound_effect(step.clone()));
+        }
+    }
+}
+
+fn play_bump_sounds(
+    mut commands: Commands,
+    audio_assets: Res<GameplayAudioAssets>,
+    mut wall_bumps: MessageReader<WallBump>,
+    player: Query<(), With<Player>>,
+) {
+    if wall_bumps.read().any(|bump| player.contains(bump.entity)) {
+        commands.spawn(sound_effect(audio_assets.bump.clone()));
+    }
+}
+
+fn play_win_sound(mut commands: Commands, audio_assets: Res<GameplayAudioAssets>) {
+    commands.spawn(sound_effect(audio_assets.win.clone()));
+}
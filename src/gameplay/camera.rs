@@ -0,0 +1,85 @@
+//! Keep the camera on the player when the map is bigger than one screen.
+
+use bevy::{prelude::*, transform::TransformSystems};
+
+use crate::{
+    gameplay::{TILE_DIM, environment::WorldMap, player::Player},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CameraFollow>();
+    app.add_systems(
+        PostUpdate,
+        follow_player
+            .before(TransformSystems::Propagate)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_camera);
+}
+
+/// How the camera tracks the player.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct CameraFollow {
+    /// How quickly the camera catches up with the player. Higher is snappier.
+    pub decay_rate: f32,
+    /// Half extents (in world units) of a box around the center of the screen
+    /// that the player can move within without the camera following.
+    pub dead_zone: Vec2,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            decay_rate: 8.0,
+            dead_zone: Vec2::new(1.5, 1.0),
+        }
+    }
+}
+
+fn follow_player(
+    time: Res<Time>,
+    follow: Res<CameraFollow>,
+    world_map: Res<WorldMap>,
+    player: Single<Ref<Transform>, (With<Player>, Without<Camera2d>)>,
+    camera: Single<(&mut Transform, &Projection), With<Camera2d>>,
+) {
+    let (mut camera_transform, projection) = camera.into_inner();
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+
+    let player_position = player.translation.truncate();
+    let camera_position = camera_transform.translation.truncate();
+
+    // Only move far enough to bring the player back inside the dead zone.
+    let offset = player_position - camera_position;
+    let target = camera_position + (offset - offset.clamp(-follow.dead_zone, follow.dead_zone));
+    let target = clamp_to_map(target, projection.area.half_size(), world_map.dimensions());
+
+    let position = if player.is_added() {
+        // Don't pan in from wherever the camera was left when a level starts.
+        clamp_to_map(
+            player_position,
+            projection.area.half_size(),
+            world_map.dimensions(),
+        )
+    } else {
+        let mut position = camera_position;
+        position.smooth_nudge(&target, follow.decay_rate, time.delta_secs());
+        position
+    };
+    camera_transform.translation = position.extend(camera_transform.translation.z);
+}
+
+/// Keep the view inside the map. The map is drawn centered on the origin, so
+/// when it is smaller than the view along an axis the camera stays centered on it.
+fn clamp_to_map(position: Vec2, half_view: Vec2, map_dim: UVec2) -> Vec2 {
+    let limit = (map_dim.as_vec2() * TILE_DIM / 2.0 - half_view).max(Vec2::ZERO);
+    position.clamp(-limit, limit)
+}
+
+fn reset_camera(mut camera_transform: Single<&mut Transform, With<Camera2d>>) {
+    camera_transform.translation = Vec3::ZERO.with_z(camera_transform.translation.z);
+}
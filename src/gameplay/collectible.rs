@@ -0,0 +1,160 @@
+//! Coins and gems scattered through a level. Walking over one picks it up and
+//! adds its points to the score in [`RunStats`]. A level can ask for every
+//! collectible to be picked up before its finish line counts; see
+//! [`Level::require_all_collectibles`].
+//!
+//! [`Level::require_all_collectibles`]: crate::gameplay::level::Level::require_all_collectibles
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::{
+        TILE_DIM,
+        environment::{WorldMap, coordinate::Coordinate},
+        interactive::{mover_hitbox, tile_transform},
+        level::{CurrentLevel, load_level},
+        maze::RestartLevel,
+        minimap::MinimapIcon,
+        movement::MovementController,
+        player::Player,
+        stats::{RunStats, reset_run_stats},
+        utils::hitbox::Hitbox,
+    },
+    screens::Screen,
+};
+
+const COIN_SIZE: f32 = 0.3 * TILE_DIM;
+const GEM_SIZE: f32 = 0.35 * TILE_DIM;
+/// #f0c83c
+const COIN_COLOR: Color = Color::srgb(0.941, 0.784, 0.235);
+/// #5ad2dc
+const GEM_COLOR: Color = Color::srgb(0.353, 0.824, 0.863);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        spawn_collectibles.after(load_level).after(reset_run_stats),
+    );
+    app.add_systems(
+        Update,
+        pick_up_collectibles
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        (clear_collectibles, spawn_collectibles)
+            .chain()
+            .run_if(in_state(Screen::Gameplay).and(on_message::<RestartLevel>))
+            .in_set(AppSystems::Update),
+    );
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CollectibleKind {
+    Coin,
+    Gem,
+}
+
+impl CollectibleKind {
+    pub fn points(self) -> u32 {
+        match self {
+            CollectibleKind::Coin => 1,
+            CollectibleKind::Gem => 5,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            CollectibleKind::Coin => "Coin",
+            CollectibleKind::Gem => "Gem",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            CollectibleKind::Coin => COIN_COLOR,
+            CollectibleKind::Gem => GEM_COLOR,
+        }
+    }
+
+    fn size(self) -> f32 {
+        match self {
+            CollectibleKind::Coin => COIN_SIZE,
+            CollectibleKind::Gem => GEM_SIZE,
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Collectible {
+    coordinate: Coordinate,
+    kind: CollectibleKind,
+}
+
+fn spawn_collectibles(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let map_dim = world_map.dimensions();
+    for (coordinate, kind) in current_level.0.markers.collectibles() {
+        let mut transform = tile_transform(coordinate, map_dim);
+        if kind == CollectibleKind::Gem {
+            // Turned on its corner, so it reads as a diamond.
+            transform.rotate_z(std::f32::consts::FRAC_PI_4);
+        }
+        commands.spawn((
+            Name::new(kind.name()),
+            Collectible { coordinate, kind },
+            Sprite::from_color(kind.color(), Vec2::splat(kind.size())),
+            transform,
+            MinimapIcon(kind.color()),
+            DespawnOnExit(Screen::Gameplay),
+        ));
+        run_stats.collectibles += 1;
+    }
+}
+
+/// Take back every collectible and the points for them, so they can be
+/// spawned again.
+fn clear_collectibles(
+    mut commands: Commands,
+    mut run_stats: ResMut<RunStats>,
+    collectibles: Query<Entity, With<Collectible>>,
+) {
+    for entity in &collectibles {
+        commands.entity(entity).despawn();
+    }
+    run_stats.score = 0;
+    run_stats.collected = 0;
+    run_stats.collectibles = 0;
+}
+
+fn pick_up_collectibles(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    mut run_stats: ResMut<RunStats>,
+    player: Single<(&Transform, &MovementController), With<Player>>,
+    collectibles: Query<(Entity, &Collectible)>,
+) {
+    let (transform, controller) = *player;
+    let Some(girth) = controller.girth else {
+        return;
+    };
+    let player_hitbox = mover_hitbox(transform, girth, world_map.dimensions());
+    for (entity, collectible) in &collectibles {
+        let center = (collectible.coordinate.to_vec2() + Vec2::splat(0.5)) * TILE_DIM;
+        let half_size = Vec2::splat(collectible.kind.size() / 2.0);
+        let hitbox = Hitbox::from_corners(center - half_size, center + half_size);
+        if player_hitbox.intersects(&hitbox) {
+            run_stats.score += collectible.kind.points();
+            run_stats.collected += 1;
+            commands.entity(entity).despawn();
+        }
+    }
+}
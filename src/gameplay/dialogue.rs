@@ -0,0 +1,242 @@
+//! Dialogue shown in a text box over the maze. Scripts are loaded from
+//! `.dialogue.ron` files in `assets/dialogue`, and each line is typed out a
+//! character at a time. Confirming (or clicking the text box) shows the whole
+//! line at once, then moves on to the next one. Clicks on the text box are
+//! consumed, so they don't also walk the player somewhere.
+//!
+//! While dialogue is open the player can't move; see [`dialogue_open`].
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    picking::pointer::PointerId,
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    input::{InputAction, action_just_pressed},
+    screens::Screen,
+    theme::widget,
+};
+
+/// How quickly lines are typed out.
+const CHARACTERS_PER_SECOND: f32 = 40.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<DialogueScript>();
+    app.init_asset_loader::<DialogueLoader>();
+    app.load_resource::<DialogueAssets>();
+
+    app.add_systems(
+        Update,
+        (
+            type_out_line.in_set(AppSystems::TickTimers),
+            advance_dialogue
+                .run_if(action_just_pressed(InputAction::Confirm))
+                .in_set(AppSystems::RecordInput),
+            update_text_box.in_set(AppSystems::Update),
+        )
+            .run_if(dialogue_open)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), close_dialogue);
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub(crate) struct DialogueAssets {
+    #[dependency]
+    pub intro: Handle<DialogueScript>,
+    #[dependency]
+    pub win: Handle<DialogueScript>,
+}
+
+impl FromWorld for DialogueAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            intro: assets.load("dialogue/intro.dialogue.ron"),
+            win: assets.load("dialogue/win.dialogue.ron"),
+        }
+    }
+}
+
+/// A conversation, shown one line at a time.
+#[derive(Asset, Reflect, Deserialize, Debug, Clone)]
+pub(crate) struct DialogueScript {
+    pub lines: Vec<DialogueLine>,
+}
+
+#[derive(Reflect, Deserialize, Debug, Clone)]
+pub(crate) struct DialogueLine {
+    #[serde(default)]
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Error)]
+pub enum DialogueLoaderError {
+    #[error("could not read dialogue file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse dialogue file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+struct DialogueLoader;
+
+impl AssetLoader for DialogueLoader {
+    type Asset = DialogueScript;
+    type Settings = ();
+    type Error = DialogueLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["dialogue.ron"]
+    }
+}
+
+/// The dialogue being shown. Only exists while the text box is open.
+#[derive(Resource, Debug)]
+pub(crate) struct ActiveDialogue {
+    lines: Vec<DialogueLine>,
+    line: usize,
+    /// How many characters of the current line are showing.
+    revealed: f32,
+}
+
+impl ActiveDialogue {
+    fn current(&self) -> &DialogueLine {
+        &self.lines[self.line]
+    }
+
+    fn is_line_revealed(&self) -> bool {
+        self.revealed as usize >= self.current().text.chars().count()
+    }
+}
+
+/// A run condition for systems that should wait for dialogue to be dismissed.
+pub(crate) fn dialogue_open(active_dialogue: Option<Res<ActiveDialogue>>) -> bool {
+    active_dialogue.is_some()
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DialogueBox;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DialogueSpeaker;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DialogueText;
+
+/// Open the text box with the given script. Scripts without any lines (or that
+/// haven't loaded) are skipped.
+pub(crate) fn start_dialogue(commands: &mut Commands, script: Option<&DialogueScript>) {
+    let Some(script) = script.filter(|script| !script.lines.is_empty()) else {
+        return;
+    };
+    commands.insert_resource(ActiveDialogue {
+        lines: script.lines.clone(),
+        line: 0,
+        revealed: 0.0,
+    });
+    commands
+        .spawn((
+            widget::text_box("Dialogue Box"),
+            DialogueBox,
+            DespawnOnExit(Screen::Gameplay),
+            children![
+                (widget::label(""), DialogueSpeaker),
+                (
+                    Name::new("Dialogue Text"),
+                    Text::default(),
+                    TextFont::from_font_size(24.0),
+                    DialogueText,
+                ),
+            ],
+        ))
+        .observe(advance_dialogue_on_click);
+}
+
+fn type_out_line(time: Res<Time>, mut active_dialogue: ResMut<ActiveDialogue>) {
+    if !active_dialogue.is_line_revealed() {
+        active_dialogue.revealed += CHARACTERS_PER_SECOND * time.delta_secs();
+    }
+}
+
+/// Finish typing the current line, or move on to the next one.
+fn advance_dialogue(
+    mut commands: Commands,
+    mut active_dialogue: ResMut<ActiveDialogue>,
+    dialogue_box: Query<Entity, With<DialogueBox>>,
+) {
+    if !active_dialogue.is_line_revealed() {
+        active_dialogue.revealed = f32::MAX;
+    } else if active_dialogue.line + 1 < active_dialogue.lines.len() {
+        active_dialogue.line += 1;
+        active_dialogue.revealed = 0.0;
+    } else {
+        commands.remove_resource::<ActiveDialogue>();
+        for entity in &dialogue_box {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Advance the dialogue, and consume the click so that it isn't also taken as
+/// a tap on the maze once the dialogue has closed.
+fn advance_dialogue_on_click(
+    click: On<Pointer<Click>>,
+    commands: Commands,
+    active_dialogue: Option<ResMut<ActiveDialogue>>,
+    dialogue_box: Query<Entity, With<DialogueBox>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mut touches: ResMut<Touches>,
+) {
+    match click.pointer_id {
+        PointerId::Mouse => {
+            mouse.clear_just_pressed(MouseButton::Left);
+        }
+        PointerId::Touch(id) => {
+            touches.clear_just_pressed(id);
+        }
+        PointerId::Custom(_) => {}
+    }
+    if let Some(active_dialogue) = active_dialogue {
+        advance_dialogue(commands, active_dialogue, dialogue_box);
+    }
+}
+
+fn update_text_box(
+    active_dialogue: Res<ActiveDialogue>,
+    mut speaker: Single<&mut Text, (With<DialogueSpeaker>, Without<DialogueText>)>,
+    mut text: Single<&mut Text, (With<DialogueText>, Without<DialogueSpeaker>)>,
+) {
+    let line = active_dialogue.current();
+    speaker.0 = line.speaker.clone().unwrap_or_default();
+    text.0 = line
+        .text
+        .chars()
+        .take(active_dialogue.revealed as usize)
+        .collect();
+}
+
+fn close_dialogue(mut commands: Commands) {
+    commands.remove_resource::<ActiveDialogue>();
+}
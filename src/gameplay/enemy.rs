@@ -0,0 +1,589 @@
+//! Enemies roaming the maze. They get about with a [`MovementController`] just
+//! like the player does, so walls stop them too. Each one patrols a route or
+//! wanders about, and gives chase once it spots the player. Being caught sends
+//! the player back to the start, or costs a life on levels that have them
+//! (see [`Level::lives`]). Losing the last life starts the level over; see
+//! [`RestartLevel`]. Either way, the player gets a moment to get away before
+//! they can be caught again.
+//!
+//! [`Level::lives`]: crate::gameplay::level::Level::lives
+
+use bevy::{prelude::*, sprite::Anchor};
+use rand::{Rng, SeedableRng, seq::IndexedRandom};
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::{
+        SCALE_FACTOR, SpriteSheet, TILE_DIM,
+        animation::PlayerAnimation,
+        dialogue::dialogue_open,
+        environment::{Direction, WorldMap, coordinate::Coordinate},
+        fog::{FogOfWar, line_of_sight},
+        interactive::mover_hitbox,
+        level::{CurrentLevel, LevelMarker, MazeSource, load_level},
+        maze::{MazeProgress, RestartLevel, seed::MazeSeed},
+        movement::MovementController,
+        pathfinding::{PathCache, distances_from, footprint, steer_along},
+        player::{Player, send_player_to_start},
+        stats::RunStats,
+        utils::{
+            render_position_from_world_array_position, world_array_position_from_render_position,
+        },
+    },
+    screens::Screen,
+    utils::Z,
+};
+
+/// How much room an enemy takes up, in world array units. A little less than
+/// the player, so it can't wedge them into a corner.
+pub const ENEMY_GIRTH: Vec2 = Vec2::splat(0.7 * TILE_DIM);
+/// How fast enemies patrol and wander, in tiles per second.
+const ROAMING_SPEED: f32 = 1.5;
+/// How fast enemies chase the player, in tiles per second.
+const CHASING_SPEED: f32 = 2.4;
+/// How many steps away a wandering enemy picks its next stop from.
+const WANDER_RANGE: u32 = 6;
+/// How long the player can't be caught for after being caught, in seconds.
+const GRACE_SECS: f32 = 2.0;
+/// How many times a second the player flashes while they can't be caught.
+const GRACE_FLASH_RATE: f32 = 8.0;
+/// #c85a5a
+const ENEMY_TINT: Color = Color::srgb(0.784, 0.353, 0.353);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LivesLeft>();
+    app.init_resource::<CatchGrace>();
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_enemies.after(load_level));
+    app.add_systems(
+        Update,
+        (
+            steer_enemies
+                .run_if(not(dialogue_open))
+                .in_set(AppSystems::RecordInput),
+            tick_grace.in_set(AppSystems::TickTimers),
+            catch_player.in_set(AppSystems::Update),
+        )
+            .run_if(in_state(MazeProgress::Seeking))
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        (
+            restart_enemies
+                .run_if(on_message::<RestartLevel>)
+                .after(catch_player),
+            hide_unseen_enemies,
+            flash_player,
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update),
+    );
+    // Otherwise enemies keep walking after the finish line is crossed.
+    app.add_systems(OnExit(MazeProgress::Seeking), stop_enemies);
+}
+
+/// How an enemy gets about when it isn't chasing the player.
+#[derive(Deserialize, Reflect, Debug, Clone, PartialEq)]
+pub(crate) enum EnemyBehaviour {
+    /// Walk between these tiles in order, then start over from the first.
+    Patrol(Vec<(i32, i32)>),
+    /// Stroll to a random tile nearby, then pick another.
+    Wander,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Enemy {
+    behaviour: EnemyBehaviour,
+    /// How far away the player can be spotted from, in tiles.
+    sight: Option<f32>,
+    /// Where the enemy started the level, in world array space.
+    spawn: Vec2,
+    /// The patrol waypoint being walked to.
+    waypoint: usize,
+    /// The tiles left to walk through, nearest last.
+    route: Vec<Coordinate>,
+    chasing: bool,
+}
+
+impl Enemy {
+    /// Where to go next when not chasing the player, from `tile`.
+    fn next_stop(
+        &mut self,
+        tile: Coordinate,
+        world_map: &WorldMap,
+        rng: &mut impl Rng,
+    ) -> Option<Coordinate> {
+        match &self.behaviour {
+            EnemyBehaviour::Patrol(waypoints) => {
+                if waypoints.is_empty() {
+                    return None;
+                }
+                let waypoint = |index: usize| {
+                    let (x, y) = waypoints[index % waypoints.len()];
+                    Coordinate::new(x, y)
+                };
+                if waypoint(self.waypoint) == tile {
+                    self.waypoint = (self.waypoint + 1) % waypoints.len();
+                }
+                Some(waypoint(self.waypoint))
+            }
+            EnemyBehaviour::Wander => {
+                let mut nearby: Vec<_> = distances_from(world_map, tile, Some(ENEMY_GIRTH))
+                    .into_iter()
+                    .filter(|(_, distance)| (1..=WANDER_RANGE).contains(distance))
+                    .map(|(coordinate, _)| coordinate)
+                    .collect();
+                // In a set order, so the same seed always picks the same tile.
+                nearby.sort_by_key(|coordinate| (coordinate.y, coordinate.x));
+                nearby.choose(rng).copied()
+            }
+        }
+    }
+
+    /// Put the enemy back where it started the level.
+    fn reset(&mut self, transform: &mut Transform, map_dim: UVec2) {
+        transform.translation =
+            render_position_from_world_array_position(self.spawn.x, self.spawn.y, map_dim)
+                .extend(transform.translation.z);
+        self.waypoint = 0;
+        self.route.clear();
+        self.chasing = false;
+    }
+}
+
+/// Makes every random choice enemies make. It's seeded from the [`MazeSeed`],
+/// or from the level's name for campaign levels, so a level always plays out
+/// the same way for the same moves.
+#[derive(Resource, Debug)]
+struct EnemyRng(ChaCha8Rng);
+
+impl EnemyRng {
+    fn new(maze_source: MazeSource, maze_seed: &MazeSeed, level_name: &str) -> Self {
+        let seed = match maze_source {
+            MazeSource::Generated => maze_seed.value,
+            MazeSource::Campaign => seed_from_name(level_name),
+        };
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+/// A seed for a level without one of its own. This is FNV-1a, which unlike
+/// `DefaultHasher` gives the same answer on every platform and Rust version.
+fn seed_from_name(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Counts down the moment after being caught when the player can't be caught
+/// again, so an enemy near the start can't catch them over and over.
+#[derive(Resource, Debug)]
+struct CatchGrace(Timer);
+
+impl Default for CatchGrace {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(GRACE_SECS, TimerMode::Once);
+        timer.finish();
+        Self(timer)
+    }
+}
+
+/// How many more times the player can be caught, on a level with lives.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct LivesLeft(pub Option<u32>);
+
+impl LivesLeft {
+    pub fn label(&self) -> String {
+        format!("Lives: {}", self.0.unwrap_or_default())
+    }
+}
+
+fn spawn_enemies(
+    mut commands: Commands,
+    sheet: Res<SpriteSheet>,
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+    maze_source: Res<MazeSource>,
+    maze_seed: Res<MazeSeed>,
+) {
+    commands.insert_resource(LivesLeft(current_level.0.lives));
+    commands.insert_resource(EnemyRng::new(
+        *maze_source,
+        &maze_seed,
+        &current_level.0.name,
+    ));
+    commands.insert_resource(CatchGrace::default());
+    let map_dim = world_map.dimensions();
+    for marker in &current_level.0.markers.0 {
+        let LevelMarker::Enemy {
+            x,
+            y,
+            behaviour,
+            sight,
+        } = marker
+        else {
+            continue;
+        };
+        let spawn = Vec2::new(*x, *y) * TILE_DIM;
+        let animation = PlayerAnimation::new(Direction::South);
+        let mut sprite = Sprite::from_atlas_image(
+            sheet.texture.clone(),
+            TextureAtlas {
+                layout: sheet.layout.clone(),
+                index: animation.get_atlas_index(),
+            },
+        );
+        sprite.color = ENEMY_TINT;
+        commands.spawn((
+            Name::new("Enemy"),
+            Enemy {
+                behaviour: behaviour.clone(),
+                sight: *sight,
+                spawn,
+                waypoint: 0,
+                route: Vec::new(),
+                chasing: false,
+            },
+            sprite,
+            animation,
+            Anchor::CENTER,
+            Transform {
+                translation: render_position_from_world_array_position(spawn.x, spawn.y, map_dim)
+                    .extend(Z.enemy),
+                scale: Vec3::splat(SCALE_FACTOR * ENEMY_GIRTH.x / TILE_DIM),
+                ..default()
+            },
+            MovementController {
+                girth: Some(ENEMY_GIRTH),
+                max_speed: ROAMING_SPEED,
+                ..default()
+            },
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+}
+
+/// Point every enemy at the player if it can see them, or along its own
+/// route if it can't.
+fn steer_enemies(
+    time: Res<Time>,
+    world_map: Res<WorldMap>,
+    mut path_cache: ResMut<PathCache>,
+    mut rng: ResMut<EnemyRng>,
+    player: Single<&Transform, With<Player>>,
+    mut enemies: Query<(&Transform, &mut MovementController, &mut Enemy), Without<Player>>,
+) {
+    let map_dim = world_map.dimensions();
+    let player_position = world_array_position(&player, map_dim);
+    let player_tile = Coordinate::from(player_position);
+    for (transform, mut controller, mut enemy) in &mut enemies {
+        let position = world_array_position(transform, map_dim);
+        let tile = Coordinate::from(position);
+        let spotted = enemy
+            .sight
+            .is_some_and(|sight| position.distance(player_position) <= sight * TILE_DIM)
+            && line_of_sight(&world_map, tile, player_tile);
+        let chase = if spotted {
+            path_cache.find_path(&world_map, tile, player_tile, controller.girth)
+        } else {
+            None
+        };
+        if let Some(path) = chase {
+            // Only plan again once the player has moved to another tile.
+            if !enemy.chasing || enemy.route.first() != Some(&player_tile) {
+                enemy.route = path.iter().rev().copied().collect();
+            }
+            enemy.chasing = true;
+        } else if enemy.chasing {
+            enemy.route.clear();
+            enemy.chasing = false;
+        }
+
+        if enemy.route.is_empty()
+            && let Some(stop) = enemy.next_stop(tile, &world_map, &mut rng.0)
+            && let Some(path) = path_cache.find_path(&world_map, tile, stop, controller.girth)
+        {
+            enemy.route = path.iter().rev().copied().collect();
+        }
+
+        controller.max_speed = if enemy.chasing {
+            CHASING_SPEED
+        } else {
+            ROAMING_SPEED
+        };
+        let step = controller.max_speed * time.delta_secs();
+        controller.intent = steer_along(
+            &mut enemy.route,
+            position,
+            step,
+            footprint(controller.girth),
+        )
+        .unwrap_or(Vec2::ZERO);
+    }
+}
+
+/// Send the player back to the start when an enemy touches them, along with
+/// every enemy, so the player gets a head start. On a level with lives, this
+/// costs one, and losing the last one starts the level over.
+fn catch_player(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+    mut run_stats: ResMut<RunStats>,
+    mut lives_left: ResMut<LivesLeft>,
+    mut grace: ResMut<CatchGrace>,
+    mut restart_level: MessageWriter<RestartLevel>,
+    player: Single<(Entity, &mut Transform, &MovementController), With<Player>>,
+    mut enemies: Query<(&mut Transform, &MovementController, &mut Enemy), Without<Player>>,
+) {
+    if !grace.0.is_finished() {
+        return;
+    }
+    let map_dim = world_map.dimensions();
+    let (player_entity, mut player_transform, player_controller) = player.into_inner();
+    let Some(player_girth) = player_controller.girth else {
+        return;
+    };
+    let player_hitbox = mover_hitbox(&player_transform, player_girth, map_dim);
+    let caught = enemies.iter().any(|(transform, controller, _)| {
+        controller
+            .girth
+            .is_some_and(|girth| mover_hitbox(transform, girth, map_dim).intersects(&player_hitbox))
+    });
+    if !caught {
+        return;
+    }
+
+    run_stats.caught += 1;
+    grace.0.reset();
+    if let Some(lives) = &mut lives_left.0 {
+        *lives = lives.saturating_sub(1);
+        if *lives == 0 {
+            restart_level.write(RestartLevel);
+        }
+    }
+
+    send_player_to_start(
+        &mut commands,
+        (player_entity, &mut player_transform),
+        &current_level.0.markers,
+        map_dim,
+        &mut run_stats,
+    );
+    for (mut transform, _, mut enemy) in &mut enemies {
+        enemy.reset(&mut transform, map_dim);
+    }
+}
+
+/// Put every enemy back where it started, with a fresh set of lives for the
+/// player and the same random choices as the first time through.
+fn restart_enemies(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+    maze_source: Res<MazeSource>,
+    maze_seed: Res<MazeSeed>,
+    mut lives_left: ResMut<LivesLeft>,
+    mut grace: ResMut<CatchGrace>,
+    mut enemies: Query<(&mut Transform, &mut Enemy)>,
+) {
+    let map_dim = world_map.dimensions();
+    for (mut transform, mut enemy) in &mut enemies {
+        enemy.reset(&mut transform, map_dim);
+    }
+    lives_left.0 = current_level.0.lives;
+    grace.0.reset();
+    commands.insert_resource(EnemyRng::new(
+        *maze_source,
+        &maze_seed,
+        &current_level.0.name,
+    ));
+}
+
+fn tick_grace(time: Res<Time>, mut grace: ResMut<CatchGrace>) {
+    grace.0.tick(time.delta());
+}
+
+/// Flash the player while they can't be caught.
+fn flash_player(grace: Res<CatchGrace>, mut sprite: Single<&mut Sprite, With<Player>>) {
+    let shown = grace.0.is_finished()
+        || ((grace.0.elapsed_secs() * GRACE_FLASH_RATE) as u32).is_multiple_of(2);
+    let alpha = if shown { 1.0 } else { 0.3 };
+    if sprite.color.alpha() != alpha {
+        sprite.color.set_alpha(alpha);
+    }
+}
+
+/// Enemies are only drawn while the player can see them.
+fn hide_unseen_enemies(
+    world_map: Res<WorldMap>,
+    fog: Res<FogOfWar>,
+    mut enemies: Query<(&Transform, &mut Visibility), With<Enemy>>,
+) {
+    let map_dim = world_map.dimensions();
+    for (transform, mut visibility) in &mut enemies {
+        let tile = Coordinate::from(world_array_position(transform, map_dim));
+        visibility.set_if_neq(if fog.is_visible(tile) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+fn stop_enemies(mut enemies: Query<&mut MovementController, With<Enemy>>) {
+    for mut controller in &mut enemies {
+        controller.intent = Vec2::ZERO;
+    }
+}
+
+fn world_array_position(transform: &Transform, map_dim: UVec2) -> Vec2 {
+    world_array_position_from_render_position(
+        transform.translation.x,
+        transform.translation.y,
+        map_dim,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{
+        environment::{GroundType, ObstructionType, Tile},
+        level::{Level, LevelMarkers},
+        player::PLAYER_GIRTH,
+    };
+
+    const PLAYER_START: Vec2 = Vec2::new(0.5, 0.5);
+    const ENEMY_SPAWN: Vec2 = Vec2::new(4.5, 4.5);
+
+    /// An open 5x5 level with `lives`, the player on its start and an enemy
+    /// standing on the player.
+    fn app(lives: Option<u32>) -> App {
+        let mut app = App::new();
+        let map_dim = UVec2::splat(5);
+        app.add_message::<RestartLevel>();
+        app.init_resource::<RunStats>();
+        app.init_resource::<CatchGrace>();
+        app.insert_resource(LivesLeft(lives));
+        app.insert_resource(MazeSource::Generated);
+        app.init_resource::<MazeSeed>();
+        app.insert_resource(WorldMap::new(
+            map_dim.x,
+            map_dim.y,
+            vec![Tile::new(GroundType::Grass, ObstructionType::None); 25],
+        ));
+        app.insert_resource(CurrentLevel(Level {
+            lives,
+            markers: LevelMarkers(vec![LevelMarker::PlayerStart {
+                x: PLAYER_START.x,
+                y: PLAYER_START.y,
+            }]),
+            ..default()
+        }));
+        app.add_systems(
+            Update,
+            (
+                catch_player,
+                restart_enemies
+                    .run_if(on_message::<RestartLevel>)
+                    .after(catch_player),
+            ),
+        );
+
+        let at = |position: Vec2| {
+            Transform::from_translation(
+                render_position_from_world_array_position(position.x, position.y, map_dim)
+                    .extend(0.0),
+            )
+        };
+        app.world_mut().spawn((
+            Player,
+            at(PLAYER_START),
+            MovementController {
+                girth: Some(PLAYER_GIRTH),
+                ..default()
+            },
+        ));
+        app.world_mut().spawn((
+            Enemy {
+                behaviour: EnemyBehaviour::Wander,
+                sight: None,
+                spawn: ENEMY_SPAWN,
+                waypoint: 0,
+                route: Vec::new(),
+                chasing: false,
+            },
+            at(PLAYER_START),
+            MovementController {
+                girth: Some(ENEMY_GIRTH),
+                ..default()
+            },
+        ));
+        app
+    }
+
+    fn enemy_position(app: &mut App) -> Vec2 {
+        let transform = app
+            .world_mut()
+            .query_filtered::<&Transform, With<Enemy>>()
+            .single(app.world())
+            .unwrap();
+        world_array_position(transform, UVec2::splat(5))
+    }
+
+    /// Put the enemy back on top of the player.
+    fn catch_again(app: &mut App) {
+        let player = *app
+            .world_mut()
+            .query_filtered::<&Transform, With<Player>>()
+            .single(app.world())
+            .unwrap();
+        let mut enemy = app
+            .world_mut()
+            .query_filtered::<&mut Transform, With<Enemy>>()
+            .single_mut(app.world_mut())
+            .unwrap();
+        *enemy = player;
+    }
+
+    #[test]
+    fn being_caught_costs_a_life_and_sends_everyone_back() {
+        let mut app = app(Some(3));
+        app.update();
+
+        assert_eq!(app.world().resource::<LivesLeft>().0, Some(2));
+        assert_eq!(app.world().resource::<RunStats>().caught, 1);
+        assert_eq!(enemy_position(&mut app), ENEMY_SPAWN);
+        assert!(!app.world().resource::<CatchGrace>().0.is_finished());
+    }
+
+    #[test]
+    fn the_player_cannot_be_caught_again_straight_away() {
+        let mut app = app(Some(3));
+        app.update();
+        catch_again(&mut app);
+        app.update();
+        assert_eq!(app.world().resource::<LivesLeft>().0, Some(2));
+
+        // Once the grace period is over, they can.
+        app.world_mut().resource_mut::<CatchGrace>().0.finish();
+        app.update();
+        assert_eq!(app.world().resource::<LivesLeft>().0, Some(1));
+    }
+
+    #[test]
+    fn losing_the_last_life_restarts_the_level() {
+        let mut app = app(Some(1));
+        app.update();
+
+        assert_eq!(app.world().resource::<Messages<RestartLevel>>().len(), 1);
+        assert_eq!(app.world().resource::<LivesLeft>().0, Some(1));
+        assert_eq!(enemy_position(&mut app), ENEMY_SPAWN);
+        assert!(!app.world().resource::<CatchGrace>().0.is_finished());
+    }
+}
@@ -2,17 +2,14 @@
 
 use bevy::prelude::*;
 use coordinate::Coordinate;
+use serde::Deserialize;
 
-use crate::{
-    SCREEN_DIM as MAP_DIM,
-    gameplay::{
-        TILE_DIM,
-        utils::{hitbox::Hitbox, render_position_from_world_array_position},
-    },
+use crate::gameplay::{
+    TILE_DIM,
+    utils::{hitbox::Hitbox, render_position_from_world_array_position},
 };
 
 pub mod coordinate;
-mod world_map_array;
 
 #[derive(Reflect, PartialEq, Clone)]
 pub enum Direction {
@@ -22,15 +19,17 @@ pub enum Direction {
     West,
 }
 
-#[derive(Reflect, Debug)]
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Default)]
 pub(in crate::gameplay) enum GroundType {
+    #[default]
     Grass,
     DirtV,
     DirtH,
 }
 
-#[derive(Reflect, Debug, PartialEq)]
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Default)]
 pub(in crate::gameplay) enum ObstructionType {
+    #[default]
     None,
     WallV,
     WallH,
@@ -38,9 +37,54 @@ pub(in crate::gameplay) enum ObstructionType {
     Rock1,
     Rock2,
     Rock3,
+    /// A locked door. It opens for a key of the same color.
+    Door(KeyColor),
+    OpenDoor(KeyColor),
+    /// A gate that levers and pressure plates raise and lower.
+    Gate,
+    OpenGate,
+}
+
+/// Matches keys to the doors they open.
+#[derive(Reflect, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyColor {
+    Red,
+    Green,
+    Blue,
+}
+
+impl KeyColor {
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyColor::Red => "Red",
+            KeyColor::Green => "Green",
+            KeyColor::Blue => "Blue",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            // #c8463c
+            KeyColor::Red => Color::srgb(0.784, 0.275, 0.235),
+            // #4fb360
+            KeyColor::Green => Color::srgb(0.310, 0.702, 0.376),
+            // #4666bf
+            KeyColor::Blue => Color::srgb(0.275, 0.400, 0.750),
+        }
+    }
 }
 
 impl GroundType {
+    /// Look up the ground type drawn with `symbol` in a level file.
+    pub(in crate::gameplay) fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            'g' => Some(GroundType::Grass),
+            '-' => Some(GroundType::DirtH),
+            '|' => Some(GroundType::DirtV),
+            _ => None,
+        }
+    }
+
     fn atlas_index(&self) -> Option<usize> {
         match self {
             GroundType::Grass => Some(2),
@@ -51,9 +95,44 @@ impl GroundType {
 }
 
 impl ObstructionType {
+    /// Look up the obstruction type drawn with `symbol` in a level file.
+    pub(in crate::gameplay) fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '.' => Some(ObstructionType::None),
+            '|' => Some(ObstructionType::WallV),
+            '-' => Some(ObstructionType::WallH),
+            '+' => Some(ObstructionType::Tower),
+            '1' => Some(ObstructionType::Rock1),
+            '2' => Some(ObstructionType::Rock2),
+            '3' => Some(ObstructionType::Rock3),
+            'R' => Some(ObstructionType::Door(KeyColor::Red)),
+            'G' => Some(ObstructionType::Door(KeyColor::Green)),
+            'B' => Some(ObstructionType::Door(KeyColor::Blue)),
+            '#' => Some(ObstructionType::Gate),
+            '_' => Some(ObstructionType::OpenGate),
+            _ => None,
+        }
+    }
+
+    /// Whether the obstruction can open and close during play. These aren't
+    /// part of the environment mesh; the `interactive` module draws them.
+    pub(in crate::gameplay) fn is_switchable(&self) -> bool {
+        matches!(
+            self,
+            ObstructionType::Door(_)
+                | ObstructionType::OpenDoor(_)
+                | ObstructionType::Gate
+                | ObstructionType::OpenGate
+        )
+    }
+
     fn atlas_index(&self) -> Option<usize> {
         match self {
-            ObstructionType::None => None,
+            ObstructionType::None
+            | ObstructionType::Door(_)
+            | ObstructionType::OpenDoor(_)
+            | ObstructionType::Gate
+            | ObstructionType::OpenGate => None,
             ObstructionType::WallV => Some(31),
             ObstructionType::WallH => Some(30),
             ObstructionType::Tower => Some(32),
@@ -64,15 +143,41 @@ impl ObstructionType {
     }
 }
 
-#[derive(Reflect, Debug)]
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Default)]
 pub(crate) struct Tile {
     ground: GroundType,
     obstruction: ObstructionType,
 }
 
 impl Tile {
+    pub(in crate::gameplay) fn new(ground: GroundType, obstruction: ObstructionType) -> Self {
+        Self {
+            ground,
+            obstruction,
+        }
+    }
+
     pub fn is_obstruction(&self) -> bool {
-        self.obstruction != ObstructionType::None
+        !matches!(
+            self.obstruction,
+            ObstructionType::None | ObstructionType::OpenDoor(_) | ObstructionType::OpenGate
+        )
+    }
+
+    pub(in crate::gameplay) fn obstruction(&self) -> ObstructionType {
+        self.obstruction
+    }
+
+    pub(in crate::gameplay) fn set_obstruction(&mut self, obstruction: ObstructionType) {
+        self.obstruction = obstruction;
+    }
+
+    /// Whether the tile's ground or obstruction has no sprite to draw it with.
+    pub(in crate::gameplay) fn is_missing_sprite(&self) -> bool {
+        self.ground.atlas_index().is_none()
+            || (self.is_obstruction()
+                && !self.obstruction.is_switchable()
+                && self.obstruction.atlas_index().is_none())
     }
 
     pub fn hitbox(&self, coordinate: Coordinate) -> Hitbox {
@@ -83,63 +188,118 @@ impl Tile {
     }
 }
 
-#[derive(Resource, Reflect, Debug)]
+/// The static world the player moves through. This is loaded from a level file
+/// (see [`crate::gameplay::level`]) and copied into a resource when gameplay starts.
+#[derive(Resource, Reflect, Debug, Clone, PartialEq, Default)]
 #[reflect(Resource)]
 pub(crate) struct WorldMap {
-    // The world is one screen square at the moment.
-    pub(crate) grid: [[Tile; MAP_DIM as usize]; MAP_DIM as usize],
+    width: u32,
+    height: u32,
+    // Row-major, so the tile at (x, y) lives at `y * width + x`.
+    tiles: Vec<Tile>,
 }
 
 impl WorldMap {
+    /// Build a map from row-major `tiles`. There must be exactly `width * height` of them.
+    pub fn new(width: u32, height: u32, tiles: Vec<Tile>) -> Self {
+        assert_eq!(
+            tiles.len(),
+            (width * height) as usize,
+            "A world map needs exactly width * height tiles!"
+        );
+        Self {
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    /// Width and height of the map in tiles.
+    pub fn dimensions(&self) -> UVec2 {
+        UVec2::new(self.width, self.height)
+    }
+
+    /// Whether the coordinate lies on the map.
+    pub fn contains(&self, coordinate: Coordinate) -> bool {
+        coordinate.x >= 0
+            && coordinate.y >= 0
+            && (coordinate.x as u32) < self.width
+            && (coordinate.y as u32) < self.height
+    }
+
+    fn index(&self, coordinate: Coordinate) -> Option<usize> {
+        self.contains(coordinate)
+            .then(|| coordinate.y as usize * self.width as usize + coordinate.x as usize)
+    }
+
     // Provide a position in world array coordinates
     pub fn at(&self, coordinate: Coordinate) -> Option<&Tile> {
-        // Ensure coordinates are within grid bounds
-        if coordinate.x < MAP_DIM as i32
-            && coordinate.x >= 0
-            && coordinate.y < MAP_DIM as i32
-            && coordinate.y >= 0
-        {
-            Some(&self.grid[coordinate.y as usize][coordinate.x as usize])
-        } else {
-            None
+        self.index(coordinate).map(|index| &self.tiles[index])
+    }
+
+    pub fn at_mut(&mut self, coordinate: Coordinate) -> Option<&mut Tile> {
+        self.index(coordinate).map(|index| &mut self.tiles[index])
+    }
+
+    /// A copy of the map with every door and gate open, for working out what
+    /// could be reached once they've all been dealt with.
+    pub fn with_everything_open(&self) -> Self {
+        let mut world_map = self.clone();
+        for tile in &mut world_map.tiles {
+            tile.obstruction = match tile.obstruction {
+                ObstructionType::Door(color) => ObstructionType::OpenDoor(color),
+                ObstructionType::Gate => ObstructionType::OpenGate,
+                obstruction => obstruction,
+            };
         }
+        world_map
     }
-}
 
-impl Default for WorldMap {
-    fn default() -> Self {
-        Self {
-            grid: world_map_array::TILE_MAP,
+    /// Take the doors and gates from `previous` rather than this map, so that
+    /// ones that have been opened and closed stay that way, and stay in step
+    /// with the entities that switch them. Doors and gates that have been
+    /// added, moved or removed are left as they were in `previous` too.
+    pub fn keep_switchables_from(&mut self, previous: &WorldMap) {
+        for (coordinate, tile) in previous.iter() {
+            if let Some(new_tile) = self.at_mut(coordinate)
+                && (tile.obstruction.is_switchable() || new_tile.obstruction.is_switchable())
+            {
+                new_tile.obstruction = tile.obstruction;
+            }
         }
     }
+
+    /// Iterate over every tile along with its coordinate, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate, &Tile)> {
+        let width = self.width as i32;
+        self.tiles.iter().enumerate().map(move |(index, tile)| {
+            let index = index as i32;
+            (Coordinate::new(index % width, index / width), tile)
+        })
+    }
 }
 
 /// Returns an iterator over (translation, atlas_index_with_depth) for all visible environment tiles.
 /// Each tile may contribute up to two sprites: ground (z=0.0) and obstruction (z=1.0).
 pub(super) fn local_environment_objects(world_map: &WorldMap) -> impl Iterator<Item = (Vec2, u16)> {
-    world_map
-        .grid
-        .iter()
-        .enumerate()
-        .flat_map(|(row, row_tiles)| {
-            row_tiles
-                .iter()
-                .enumerate()
-                .flat_map(move |(column, tile)| {
-                    let base_translation =
-                        render_position_from_world_array_position(column as f32, row as f32);
-
-                    let mut entries = Vec::new();
-
-                    if let Some(index) = tile.ground.atlas_index() {
-                        entries.push((base_translation, index as u16));
-                    }
-
-                    if let Some(index) = tile.obstruction.atlas_index() {
-                        entries.push((base_translation, index as u16));
-                    }
-
-                    entries
-                })
-        })
+    let map_dim = world_map.dimensions();
+    world_map.iter().flat_map(move |(coordinate, tile)| {
+        let base_translation = render_position_from_world_array_position(
+            coordinate.x as f32,
+            coordinate.y as f32,
+            map_dim,
+        );
+
+        let mut entries = Vec::new();
+
+        if let Some(index) = tile.ground.atlas_index() {
+            entries.push((base_translation, index as u16));
+        }
+
+        if let Some(index) = tile.obstruction.atlas_index() {
+            entries.push((base_translation, index as u16));
+        }
+
+        entries
+    })
 }
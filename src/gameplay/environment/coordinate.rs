@@ -1,7 +1,7 @@
-use bevy::math::Vec2;
+use bevy::{math::Vec2, reflect::Reflect};
 
 /// A 2-dimensional grid coordinate in world array space.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Coordinate {
     pub x: i32,
     pub y: i32,
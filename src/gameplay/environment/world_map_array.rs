@@ -1,511 +0,0 @@
-// Auto-generated tile map
-use super::{GroundType, ObstructionType, Tile};
-
-pub const TILE_MAP: [[Tile; 11]; 11] = [
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Tower,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::DirtV,
-            obstruction: ObstructionType::Rock2,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Rock1,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Rock2,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Rock1,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Rock2,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Rock3,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Tower,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Rock1,
-        },
-        Tile {
-            ground: GroundType::DirtH,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::DirtV,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Tower,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Tower,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::DirtH,
-            obstruction: ObstructionType::None,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallV,
-        },
-    ],
-    [
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::WallH,
-        },
-        Tile {
-            ground: GroundType::Grass,
-            obstruction: ObstructionType::Tower,
-        },
-    ],
-];
@@ -0,0 +1,251 @@
+//! Fog of war. The player only sees the tiles in their line of sight, out to
+//! the level's torch radius (see [`Level::torch_radius`]). Obstructions block
+//! sight. Tiles that have been seen stay explored and are drawn dimmed once
+//! they're out of sight. Tiles that haven't been seen yet are hidden.
+//!
+//! [`FogOfWar`] keeps track of what's explored and visible in world array
+//! space, and a mask drawn over the environment darkens the rest.
+//!
+//! [`Level::torch_radius`]: crate::gameplay::level::Level::torch_radius
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::{
+        TILE_DIM,
+        environment::{WorldMap, coordinate::Coordinate},
+        level::{CurrentLevel, load_level},
+        player::Player,
+        utils::world_array_position_from_render_position,
+    },
+    screens::Screen,
+    utils::Z,
+};
+
+const UNEXPLORED_COLOR: Color = Color::BLACK;
+const OUT_OF_SIGHT_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<FogOfWar>();
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_fog.after(load_level));
+    app.add_systems(
+        Update,
+        update_fog
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// What the player has explored and can see right now, for every tile of the
+/// [`WorldMap`].
+#[derive(Resource, Debug, Default)]
+pub(crate) struct FogOfWar {
+    dimensions: UVec2,
+    // Row-major, like `WorldMap`.
+    explored: Vec<bool>,
+    visible: Vec<bool>,
+    /// The tile the player was looking from last time sight was worked out.
+    viewer: Option<Coordinate>,
+}
+
+impl FogOfWar {
+    fn new(dimensions: UVec2) -> Self {
+        let tiles = (dimensions.x * dimensions.y) as usize;
+        Self {
+            dimensions,
+            explored: vec![false; tiles],
+            visible: vec![false; tiles],
+            viewer: None,
+        }
+    }
+
+    fn index(&self, coordinate: Coordinate) -> Option<usize> {
+        let on_map = coordinate.x >= 0
+            && coordinate.y >= 0
+            && (coordinate.x as u32) < self.dimensions.x
+            && (coordinate.y as u32) < self.dimensions.y;
+        on_map.then(|| coordinate.y as usize * self.dimensions.x as usize + coordinate.x as usize)
+    }
+
+    /// Whether the player has ever seen the tile.
+    pub fn is_explored(&self, coordinate: Coordinate) -> bool {
+        self.index(coordinate)
+            .is_some_and(|index| self.explored[index])
+    }
+
+    /// Whether the player can see the tile right now.
+    pub fn is_visible(&self, coordinate: Coordinate) -> bool {
+        self.index(coordinate)
+            .is_some_and(|index| self.visible[index])
+    }
+
+    /// Work out what can be seen from the center of the `viewer` tile.
+    ///
+    /// Open tiles are visible when nothing blocks a straight line to their
+    /// center. Looking down a corridor, the walls either side would block the
+    /// lines to each other, so obstructions are visible when they're next to a
+    /// visible open tile instead.
+    fn look_from(&mut self, world_map: &WorldMap, viewer: Coordinate, torch_radius: Option<f32>) {
+        self.viewer = Some(viewer);
+        self.visible.fill(false);
+        let in_range = |coordinate: Coordinate| {
+            torch_radius.is_none_or(|radius| {
+                coordinate.to_vec2().distance(viewer.to_vec2()) * TILE_DIM <= radius
+            })
+        };
+
+        let seen_open = world_map
+            .iter()
+            .filter(|(coordinate, tile)| {
+                !tile.is_obstruction()
+                    && in_range(*coordinate)
+                    && line_of_sight(world_map, viewer, *coordinate)
+            })
+            .map(|(coordinate, _)| coordinate)
+            .collect::<Vec<_>>();
+        for coordinate in seen_open {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let neighbour = Coordinate::new(coordinate.x + dx, coordinate.y + dy);
+                    let lit = (dx == 0 && dy == 0)
+                        || world_map
+                            .at(neighbour)
+                            .is_some_and(|tile| tile.is_obstruction() && in_range(neighbour));
+                    if let Some(index) = self.index(neighbour).filter(|_| lit) {
+                        self.visible[index] = true;
+                        self.explored[index] = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether a straight line from the center of `from` to the center of `to`
+/// only passes through open tiles. The tiles at either end don't count.
+pub(super) fn line_of_sight(world_map: &WorldMap, from: Coordinate, to: Coordinate) -> bool {
+    let start = from.to_vec2() + Vec2::splat(0.5);
+    let delta = to.to_vec2() - from.to_vec2();
+    let step = Coordinate::new(delta.x.signum() as i32, delta.y.signum() as i32);
+    // How far along the line (from 0 to 1) each step across a tile goes, and
+    // where the line first crosses into the next column and row.
+    let t_delta = Vec2::new(1.0 / delta.x.abs(), 1.0 / delta.y.abs());
+    let mut t_max = Vec2::new(
+        if delta.x == 0.0 {
+            f32::INFINITY
+        } else {
+            0.5 * t_delta.x
+        },
+        if delta.y == 0.0 {
+            f32::INFINITY
+        } else {
+            0.5 * t_delta.y
+        },
+    );
+
+    let mut tile = Coordinate::from(start);
+    while tile != to {
+        if tile != from && world_map.at(tile).is_none_or(|tile| tile.is_obstruction()) {
+            return false;
+        }
+        if t_max.x < t_max.y {
+            tile.x += step.x;
+            t_max.x += t_delta.x;
+        } else {
+            tile.y += step.y;
+            t_max.y += t_delta.y;
+        }
+    }
+    true
+}
+
+/// Marks the sprite that darkens the tiles the player can't see. It has one
+/// pixel per tile.
+#[derive(Component, Debug)]
+struct FogMask;
+
+fn spawn_fog(mut commands: Commands, world_map: Res<WorldMap>, mut images: ResMut<Assets<Image>>) {
+    let map_dim = world_map.dimensions();
+    commands.insert_resource(FogOfWar::new(map_dim));
+    commands.spawn((
+        Name::new("Fog Mask"),
+        FogMask,
+        Sprite {
+            image: images.add(fog_image(map_dim)),
+            custom_size: Some(map_dim.as_vec2() * TILE_DIM),
+            ..default()
+        },
+        // The map is centered on the origin, and so is the sprite.
+        Transform::from_xyz(0.0, 0.0, Z.fog),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn fog_image(map_dim: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: map_dim.x,
+            height: map_dim.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &UNEXPLORED_COLOR.to_srgba().to_u8_array(),
+        TextureFormat::Rgba8UnormSrgb,
+        // Kept in the main world too, so it can be redrawn as the player moves.
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// Work out what the player can see whenever they step onto another tile, or
+/// the map changes, and redraw the mask.
+fn update_fog(
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+    mut fog: ResMut<FogOfWar>,
+    player: Single<&Transform, With<Player>>,
+    mask: Single<&mut Sprite, With<FogMask>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let map_dim = world_map.dimensions();
+    let viewer = Coordinate::from(world_array_position_from_render_position(
+        player.translation.x,
+        player.translation.y,
+        map_dim,
+    ));
+    let map_changed = world_map.is_changed() || current_level.is_changed();
+    if fog.viewer == Some(viewer) && !map_changed {
+        return;
+    }
+
+    let mut sprite = mask.into_inner();
+    if fog.dimensions != map_dim {
+        *fog = FogOfWar::new(map_dim);
+        sprite.image = images.add(fog_image(map_dim));
+        sprite.custom_size = Some(map_dim.as_vec2() * TILE_DIM);
+    }
+    fog.look_from(&world_map, viewer, current_level.0.torch_radius);
+
+    let Some(image) = images.get_mut(&sprite.image) else {
+        return;
+    };
+    for (coordinate, _) in world_map.iter() {
+        let color = if fog.is_visible(coordinate) {
+            Color::NONE
+        } else if fog.is_explored(coordinate) {
+            OUT_OF_SIGHT_COLOR
+        } else {
+            UNEXPLORED_COLOR
+        };
+        // Both the image and world array space have y going down.
+        let _ = image.set_color_at(coordinate.x as u32, coordinate.y as u32, color);
+    }
+}
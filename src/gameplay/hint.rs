@@ -0,0 +1,151 @@
+//! Hints for stuck players. Asking for one lays a trail of breadcrumbs along
+//! the shortest path to the finish line, which fades away from the player's end
+//! first. The trail is drawn over the fog of war, so it shows the way through
+//! unexplored parts of the maze too. Hints have a cooldown, and each one is
+//! counted in [`RunStats`].
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::{
+        TILE_DIM,
+        dialogue::dialogue_open,
+        environment::{WorldMap, coordinate::Coordinate},
+        maze::{FinishLine, MazeProgress},
+        movement::MovementController,
+        pathfinding::{PathCache, footprint, footprints_within},
+        player::Player,
+        stats::RunStats,
+        utils::{
+            render_position_from_world_array_position, world_array_position_from_render_position,
+        },
+    },
+    input::{InputAction, action_just_pressed},
+    screens::Screen,
+    utils::Z,
+};
+
+/// How long before another hint can be asked for.
+const HINT_COOLDOWN_SECS: f32 = 10.0;
+/// How long the first breadcrumb of a trail lasts.
+const BREADCRUMB_SECS: f32 = 2.0;
+/// How much longer each breadcrumb lasts than the one before it.
+const BREADCRUMB_DELAY_SECS: f32 = 0.1;
+const BREADCRUMB_SIZE: f32 = 0.25 * TILE_DIM;
+const BREADCRUMB_COLOR: Color = Color::srgb(0.988, 0.984, 0.800);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), reset_hint_cooldown);
+    app.add_systems(
+        Update,
+        (
+            (tick_hint_cooldown, fade_breadcrumbs).in_set(AppSystems::TickTimers),
+            show_hint
+                .run_if(
+                    in_state(MazeProgress::Seeking)
+                        .and(not(dialogue_open))
+                        .and(hint_ready)
+                        .and(action_just_pressed(InputAction::Hint)),
+                )
+                .in_set(AppSystems::RecordInput),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// Counts down to when the next hint can be asked for.
+#[derive(Resource, Debug)]
+struct HintCooldown(Timer);
+
+fn reset_hint_cooldown(mut commands: Commands) {
+    let mut timer = Timer::from_seconds(HINT_COOLDOWN_SECS, TimerMode::Once);
+    // The first hint doesn't have to wait.
+    timer.finish();
+    commands.insert_resource(HintCooldown(timer));
+}
+
+fn tick_hint_cooldown(time: Res<Time>, mut cooldown: ResMut<HintCooldown>) {
+    cooldown.0.tick(time.delta());
+}
+
+fn hint_ready(cooldown: Option<Res<HintCooldown>>) -> bool {
+    cooldown.is_some_and(|cooldown| cooldown.0.is_finished())
+}
+
+/// One dot of a hint trail. It fades out as its timer runs down.
+#[derive(Component, Debug)]
+struct Breadcrumb(Timer);
+
+fn show_hint(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    mut path_cache: ResMut<PathCache>,
+    mut cooldown: ResMut<HintCooldown>,
+    mut run_stats: ResMut<RunStats>,
+    player: Single<(&Transform, &MovementController), With<Player>>,
+    finish_lines: Query<&FinishLine>,
+    breadcrumbs: Query<Entity, With<Breadcrumb>>,
+) {
+    let (transform, controller) = *player;
+    let map_dim = world_map.dimensions();
+    let start = Coordinate::from(world_array_position_from_render_position(
+        transform.translation.x,
+        transform.translation.y,
+        map_dim,
+    ));
+
+    // Head for whichever tile of a finish line is closest.
+    let footprint = footprint(controller.girth);
+    let path = finish_lines
+        .iter()
+        .flat_map(|finish_line| footprints_within(&finish_line.hitbox, footprint))
+        .filter_map(|finish| {
+            path_cache
+                .find_path(&world_map, start, finish, controller.girth)
+                .map(<[Coordinate]>::to_vec)
+        })
+        .min_by_key(Vec::len);
+    let Some(path) = path else {
+        return;
+    };
+
+    for entity in &breadcrumbs {
+        commands.entity(entity).despawn();
+    }
+    // The player is already on the first tile.
+    for (index, coordinate) in path.iter().skip(1).enumerate() {
+        let center = (coordinate.to_vec2() + Vec2::splat(footprint as f32 / 2.0)) * TILE_DIM;
+        let lifetime = BREADCRUMB_SECS + index as f32 * BREADCRUMB_DELAY_SECS;
+        commands.spawn((
+            Name::new("Breadcrumb"),
+            Breadcrumb(Timer::from_seconds(lifetime, TimerMode::Once)),
+            Sprite::from_color(BREADCRUMB_COLOR, Vec2::splat(BREADCRUMB_SIZE)),
+            Transform::from_translation(
+                render_position_from_world_array_position(center.x, center.y, map_dim)
+                    .extend(Z.overlay),
+            ),
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+    cooldown.0.reset();
+    run_stats.hints += 1;
+}
+
+fn fade_breadcrumbs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut breadcrumbs: Query<(Entity, &mut Breadcrumb, &mut Sprite)>,
+) {
+    for (entity, mut breadcrumb, mut sprite) in &mut breadcrumbs {
+        breadcrumb.0.tick(time.delta());
+        if breadcrumb.0.is_finished() {
+            commands.entity(entity).despawn();
+        } else {
+            sprite
+                .color
+                .set_alpha(breadcrumb.0.fraction_remaining().min(0.5) * 2.0);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Information about the current run, drawn over the maze.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems,
+    gameplay::{
+        enemy::LivesLeft,
+        interactive::KeyRing,
+        level::{CurrentLevel, LevelMarker, load_level},
+        stats::RunStats,
+    },
+    screens::Screen,
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_hud.after(load_level));
+    app.add_systems(
+        Update,
+        update_run_stats
+            .run_if(in_state(Screen::Gameplay).and(resource_changed::<RunStats>))
+            .in_set(AppSystems::Update),
+    );
+    app.add_systems(
+        Update,
+        update_key_ring
+            .run_if(in_state(Screen::Gameplay).and(resource_changed::<KeyRing>))
+            .in_set(AppSystems::Update),
+    );
+    app.add_systems(
+        Update,
+        update_lives
+            .run_if(in_state(Screen::Gameplay).and(resource_changed::<LivesLeft>))
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// A line of the HUD showing one of the [`RunStats`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+enum RunStat {
+    Time,
+    Distance,
+    WallBumps,
+    Hints,
+    Score,
+    Collected,
+    /// How many have been collected, on a level that needs them all.
+    CollectedAllNeeded,
+    Caught,
+}
+
+/// The HUD line listing the keys the player is carrying. Only levels with
+/// keys have one.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct KeyRingLabel;
+
+/// The HUD line showing how many lives are left. Only levels with lives have
+/// one.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct LivesLabel;
+
+fn spawn_hud(mut commands: Commands, current_level: Res<CurrentLevel>) {
+    let hud = commands
+        .spawn((
+            Name::new("HUD"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: px(10),
+                left: px(10),
+                flex_direction: FlexDirection::Column,
+                row_gap: px(5),
+                ..default()
+            },
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+            children![
+                widget::label(current_level.0.name.clone()),
+                (widget::label(""), RunStat::Time),
+                (widget::label(""), RunStat::Distance),
+                (widget::label(""), RunStat::WallBumps),
+                (widget::label(""), RunStat::Hints),
+            ],
+        ))
+        .id();
+    if current_level.0.markers.collectibles().next().is_some() {
+        commands.spawn((widget::label(""), RunStat::Score, ChildOf(hud)));
+        let collected = if current_level.0.require_all_collectibles {
+            RunStat::CollectedAllNeeded
+        } else {
+            RunStat::Collected
+        };
+        commands.spawn((widget::label(""), collected, ChildOf(hud)));
+    }
+    let has_keys = current_level
+        .0
+        .markers
+        .0
+        .iter()
+        .any(|marker| matches!(marker, LevelMarker::Key { .. }));
+    if has_keys {
+        commands.spawn((widget::label(""), KeyRingLabel, ChildOf(hud)));
+    }
+    let has_enemies = current_level
+        .0
+        .markers
+        .0
+        .iter()
+        .any(|marker| matches!(marker, LevelMarker::Enemy { .. }));
+    if has_enemies {
+        commands.spawn((widget::label(""), RunStat::Caught, ChildOf(hud)));
+    }
+    if current_level.0.lives.is_some() {
+        commands.spawn((widget::label(""), LivesLabel, ChildOf(hud)));
+    }
+}
+
+fn update_run_stats(run_stats: Res<RunStats>, mut labels: Query<(&RunStat, &mut Text)>) {
+    for (stat, mut text) in &mut labels {
+        text.0 = match stat {
+            RunStat::Time => run_stats.time_label(),
+            RunStat::Distance => run_stats.distance_label(),
+            RunStat::WallBumps => run_stats.wall_bumps_label(),
+            RunStat::Hints => run_stats.hints_label(),
+            RunStat::Score => run_stats.score_label(),
+            RunStat::Collected => run_stats.collected_label(),
+            RunStat::CollectedAllNeeded => {
+                format!("{} (all needed)", run_stats.collected_label())
+            }
+            RunStat::Caught => run_stats.caught_label(),
+        };
+    }
+}
+
+fn update_key_ring(key_ring: Res<KeyRing>, mut labels: Query<&mut Text, With<KeyRingLabel>>) {
+    for mut text in &mut labels {
+        text.0 = key_ring.label();
+    }
+}
+
+fn update_lives(lives_left: Res<LivesLeft>, mut labels: Query<&mut Text, With<LivesLabel>>) {
+    for mut text in &mut labels {
+        text.0 = lives_left.label();
+    }
+}
@@ -0,0 +1,481 @@
+//! Things in the maze the player can interact with, drawn on the `interactive`
+//! layer:
+//! - Keys, picked up by walking over them and kept in the [`KeyRing`].
+//! - Doors, which open when the player walks into them holding a key of the
+//!   same color. The key is used up.
+//! - Levers, flipped with the interact action, and pressure plates, pressed
+//!   while anything stands on them. Both toggle the gates wired to them.
+//!
+//! Doors and gates are obstructions in the [`WorldMap`], so opening and
+//! closing them changes what blocks movement, paths and sight straight away.
+//! A gate won't close on anything standing in it; it waits until it's clear.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::{
+        TILE_DIM,
+        dialogue::dialogue_open,
+        environment::{KeyColor, ObstructionType, WorldMap, coordinate::Coordinate},
+        level::{CurrentLevel, LevelMarker, load_level},
+        maze::RestartLevel,
+        minimap::MinimapIcon,
+        movement::MovementController,
+        player::Player,
+        utils::{
+            hitbox::Hitbox, render_position_from_world_array_position,
+            world_array_position_from_render_position,
+        },
+    },
+    input::{InputAction, action_just_pressed},
+    screens::Screen,
+    utils::Z,
+};
+
+/// How close the player has to get to a door to try their keys on it.
+const DOOR_REACH: f32 = 0.1 * TILE_DIM;
+const KEY_SIZE: f32 = 0.4 * TILE_DIM;
+const LEVER_SIZE: Vec2 = Vec2::new(0.2 * TILE_DIM, 0.6 * TILE_DIM);
+const PLATE_SIZE: f32 = 0.7 * TILE_DIM;
+/// #6e6e78
+const GATE_COLOR: Color = Color::srgb(0.431, 0.431, 0.471);
+/// #fcfbcc
+const SWITCH_ON_COLOR: Color = Color::srgb(0.988, 0.984, 0.800);
+/// #8c8264
+const SWITCH_OFF_COLOR: Color = Color::srgb(0.549, 0.510, 0.392);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<KeyRing>();
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        spawn_interactives.after(load_level),
+    );
+    app.add_systems(
+        Update,
+        (
+            flip_levers
+                .run_if(not(dialogue_open).and(action_just_pressed(InputAction::Interact)))
+                .in_set(AppSystems::RecordInput),
+            (
+                pick_up_keys,
+                unlock_doors,
+                press_plates,
+                move_gates,
+                draw_switches,
+                draw_doors_and_gates.run_if(resource_changed::<WorldMap>),
+            )
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        restart_interactives
+            .run_if(in_state(Screen::Gameplay).and(on_message::<RestartLevel>))
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// The keys the player is carrying.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct KeyRing(pub Vec<KeyColor>);
+
+impl KeyRing {
+    pub fn label(&self) -> String {
+        if self.0.is_empty() {
+            return "Keys: none".to_string();
+        }
+        let names = self.0.iter().map(|key| key.name()).collect::<Vec<_>>();
+        format!("Keys: {}", names.join(", "))
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Key {
+    coordinate: Coordinate,
+    color: KeyColor,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Door {
+    coordinate: Coordinate,
+    color: KeyColor,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Gate {
+    coordinate: Coordinate,
+    /// Whether the gate should be up. It only goes up once nothing is in
+    /// the way.
+    raised: bool,
+    /// Whether the gate was up when the level started.
+    raised_at_start: bool,
+}
+
+/// A lever or pressure plate, and the gates it toggles.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Switch {
+    coordinate: Coordinate,
+    gates: Vec<Coordinate>,
+    on: bool,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Lever;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct PressurePlate;
+
+/// Where an interactive object sits when it's centered on `coordinate`.
+pub(super) fn tile_transform(coordinate: Coordinate, map_dim: UVec2) -> Transform {
+    let center = coordinate.to_vec2() + Vec2::splat(0.5);
+    Transform::from_translation(
+        render_position_from_world_array_position(
+            center.x * TILE_DIM,
+            center.y * TILE_DIM,
+            map_dim,
+        )
+        .extend(Z.interactive),
+    )
+}
+
+fn tile_hitbox(coordinate: Coordinate) -> Hitbox {
+    Hitbox::from_corners(
+        coordinate.to_vec2() * TILE_DIM,
+        (coordinate.to_vec2() + Vec2::ONE) * TILE_DIM,
+    )
+}
+
+/// The space taken up by something that moves, in world array space.
+pub(super) fn mover_hitbox(transform: &Transform, girth: Vec2, map_dim: UVec2) -> Hitbox {
+    let position = world_array_position_from_render_position(
+        transform.translation.x,
+        transform.translation.y,
+        map_dim,
+    );
+    Hitbox::from_corners(position - girth / 2.0, position + girth / 2.0)
+}
+
+fn spawn_interactives(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+) {
+    commands.insert_resource(KeyRing::default());
+    let map_dim = world_map.dimensions();
+
+    for (coordinate, tile) in world_map.iter() {
+        match tile.obstruction() {
+            ObstructionType::Door(color) | ObstructionType::OpenDoor(color) => {
+                commands.spawn((
+                    Name::new(format!("{} Door", color.name())),
+                    Door { coordinate, color },
+                    Sprite::from_color(color.color().darker(0.2), Vec2::splat(TILE_DIM)),
+                    tile_transform(coordinate, map_dim),
+                    DespawnOnExit(Screen::Gameplay),
+                ));
+            }
+            ObstructionType::Gate | ObstructionType::OpenGate => {
+                commands.spawn((
+                    Name::new("Gate"),
+                    Gate {
+                        coordinate,
+                        raised: tile.is_obstruction(),
+                        raised_at_start: tile.is_obstruction(),
+                    },
+                    Sprite::from_color(GATE_COLOR, Vec2::splat(TILE_DIM)),
+                    tile_transform(coordinate, map_dim),
+                    DespawnOnExit(Screen::Gameplay),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    for marker in &current_level.0.markers.0 {
+        match marker {
+            LevelMarker::Key { x, y, color } => {
+                commands.spawn(key(Coordinate::new(*x, *y), *color, map_dim));
+            }
+            LevelMarker::Lever { x, y, gates } => {
+                let coordinate = Coordinate::new(*x, *y);
+                commands.spawn((
+                    Name::new("Lever"),
+                    Lever,
+                    switch(coordinate, gates),
+                    Sprite::from_color(SWITCH_OFF_COLOR, LEVER_SIZE),
+                    tile_transform(coordinate, map_dim),
+                    MinimapIcon(SWITCH_ON_COLOR),
+                    DespawnOnExit(Screen::Gameplay),
+                ));
+            }
+            LevelMarker::PressurePlate { x, y, gates } => {
+                let coordinate = Coordinate::new(*x, *y);
+                commands.spawn((
+                    Name::new("Pressure Plate"),
+                    PressurePlate,
+                    switch(coordinate, gates),
+                    Sprite::from_color(SWITCH_OFF_COLOR, Vec2::splat(PLATE_SIZE)),
+                    tile_transform(coordinate, map_dim),
+                    DespawnOnExit(Screen::Gameplay),
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn key(coordinate: Coordinate, color: KeyColor, map_dim: UVec2) -> impl Bundle {
+    (
+        Name::new(format!("{} Key", color.name())),
+        Key { coordinate, color },
+        Sprite::from_color(color.color(), Vec2::splat(KEY_SIZE)),
+        tile_transform(coordinate, map_dim),
+        MinimapIcon(color.color()),
+        DespawnOnExit(Screen::Gameplay),
+    )
+}
+
+fn switch(coordinate: Coordinate, gates: &[(i32, i32)]) -> Switch {
+    Switch {
+        coordinate,
+        gates: gates.iter().map(|&(x, y)| Coordinate::new(x, y)).collect(),
+        on: false,
+    }
+}
+
+/// Toggle every gate wired to `switch`.
+fn toggle_gates(switch: &Switch, gates: &mut Query<&mut Gate>) {
+    for mut gate in gates {
+        if switch.gates.contains(&gate.coordinate) {
+            gate.raised = !gate.raised;
+        }
+    }
+}
+
+/// Put every key back where it started, lock every door and set every gate
+/// and switch back the way it was when the level started.
+fn restart_interactives(
+    mut commands: Commands,
+    mut world_map: ResMut<WorldMap>,
+    current_level: Res<CurrentLevel>,
+    mut key_ring: ResMut<KeyRing>,
+    keys: Query<Entity, With<Key>>,
+    doors: Query<&Door>,
+    mut gates: Query<&mut Gate>,
+    mut switches: Query<&mut Switch>,
+) {
+    key_ring.0.clear();
+    for entity in &keys {
+        commands.entity(entity).despawn();
+    }
+    let map_dim = world_map.dimensions();
+    for marker in &current_level.0.markers.0 {
+        if let LevelMarker::Key { x, y, color } = marker {
+            commands.spawn(key(Coordinate::new(*x, *y), *color, map_dim));
+        }
+    }
+
+    for door in &doors {
+        if let Some(tile) = world_map.at_mut(door.coordinate) {
+            tile.set_obstruction(ObstructionType::Door(door.color));
+        }
+    }
+    for mut gate in &mut gates {
+        gate.raised = gate.raised_at_start;
+        if let Some(tile) = world_map.at_mut(gate.coordinate) {
+            tile.set_obstruction(if gate.raised {
+                ObstructionType::Gate
+            } else {
+                ObstructionType::OpenGate
+            });
+        }
+    }
+    for mut switch in &mut switches {
+        switch.on = false;
+    }
+}
+
+fn pick_up_keys(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    mut key_ring: ResMut<KeyRing>,
+    player: Single<(&Transform, &MovementController), With<Player>>,
+    keys: Query<(Entity, &Key)>,
+) {
+    let (transform, controller) = *player;
+    let Some(girth) = controller.girth else {
+        return;
+    };
+    let player_hitbox = mover_hitbox(transform, girth, world_map.dimensions());
+    for (entity, key) in &keys {
+        let center = (key.coordinate.to_vec2() + Vec2::splat(0.5)) * TILE_DIM;
+        let key_hitbox = Hitbox::from_corners(
+            center - Vec2::splat(KEY_SIZE / 2.0),
+            center + Vec2::splat(KEY_SIZE / 2.0),
+        );
+        if player_hitbox.intersects(&key_hitbox) {
+            key_ring.0.push(key.color);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn unlock_doors(
+    mut world_map: ResMut<WorldMap>,
+    mut key_ring: ResMut<KeyRing>,
+    player: Single<(&Transform, &MovementController), With<Player>>,
+    doors: Query<&Door>,
+) {
+    let (transform, controller) = *player;
+    let Some(girth) = controller.girth else {
+        return;
+    };
+    let reach = mover_hitbox(
+        transform,
+        girth + Vec2::splat(2.0 * DOOR_REACH),
+        world_map.dimensions(),
+    );
+    for door in &doors {
+        let Some(ObstructionType::Door(color)) =
+            world_map.at(door.coordinate).map(|tile| tile.obstruction())
+        else {
+            continue;
+        };
+        let Some(key) = key_ring.0.iter().position(|key| *key == color) else {
+            continue;
+        };
+        if reach.intersects(&tile_hitbox(door.coordinate)) {
+            key_ring.0.remove(key);
+            if let Some(tile) = world_map.at_mut(door.coordinate) {
+                tile.set_obstruction(ObstructionType::OpenDoor(color));
+            }
+        }
+    }
+}
+
+fn flip_levers(
+    world_map: Res<WorldMap>,
+    player: Single<(&Transform, &MovementController), With<Player>>,
+    mut levers: Query<&mut Switch, With<Lever>>,
+    mut gates: Query<&mut Gate>,
+) {
+    let (transform, controller) = *player;
+    let Some(girth) = controller.girth else {
+        return;
+    };
+    let player_hitbox = mover_hitbox(transform, girth, world_map.dimensions());
+    for mut lever in &mut levers {
+        if player_hitbox.intersects(&tile_hitbox(lever.coordinate)) {
+            lever.on = !lever.on;
+            toggle_gates(&lever, &mut gates);
+        }
+    }
+}
+
+fn press_plates(
+    world_map: Res<WorldMap>,
+    movers: Query<(&Transform, &MovementController)>,
+    mut plates: Query<&mut Switch, With<PressurePlate>>,
+    mut gates: Query<&mut Gate>,
+) {
+    let map_dim = world_map.dimensions();
+    for mut plate in &mut plates {
+        let plate_hitbox = tile_hitbox(plate.coordinate);
+        let pressed = movers.iter().any(|(transform, controller)| {
+            controller.girth.is_some_and(|girth| {
+                mover_hitbox(transform, girth, map_dim).intersects(&plate_hitbox)
+            })
+        });
+        if pressed != plate.on {
+            plate.on = pressed;
+            toggle_gates(&plate, &mut gates);
+        }
+    }
+}
+
+/// Open and close gates to match what their switches want.
+fn move_gates(
+    mut world_map: ResMut<WorldMap>,
+    gates: Query<&Gate>,
+    movers: Query<(&Transform, &MovementController)>,
+) {
+    let map_dim = world_map.dimensions();
+    for gate in &gates {
+        let Some(tile) = world_map.at(gate.coordinate) else {
+            continue;
+        };
+        if tile.is_obstruction() == gate.raised {
+            continue;
+        }
+        let gate_hitbox = tile_hitbox(gate.coordinate);
+        let blocked = gate.raised
+            && movers.iter().any(|(transform, controller)| {
+                controller.girth.is_some_and(|girth| {
+                    mover_hitbox(transform, girth, map_dim).intersects(&gate_hitbox)
+                })
+            });
+        if blocked {
+            continue;
+        }
+        if let Some(tile) = world_map.at_mut(gate.coordinate) {
+            tile.set_obstruction(if gate.raised {
+                ObstructionType::Gate
+            } else {
+                ObstructionType::OpenGate
+            });
+        }
+    }
+}
+
+fn draw_switches(mut switches: Query<(&Switch, &mut Sprite), Changed<Switch>>) {
+    for (switch, mut sprite) in &mut switches {
+        sprite.color = if switch.on {
+            SWITCH_ON_COLOR
+        } else {
+            SWITCH_OFF_COLOR
+        };
+    }
+}
+
+/// Show doors and gates as they are in the [`WorldMap`]: open doors aren't
+/// drawn at all, and open gates are faded.
+fn draw_doors_and_gates(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    mut doors: Query<(Entity, &Door, &mut Visibility, Has<MinimapIcon>)>,
+    mut gates: Query<(&Gate, &mut Sprite)>,
+) {
+    // Only touch the minimap icon when a door is unlocked, since changing it
+    // redraws the minimap.
+    for (entity, door, mut visibility, has_icon) in &mut doors {
+        let locked = world_map.at(door.coordinate).map(|tile| tile.obstruction());
+        if let Some(ObstructionType::Door(color)) = locked {
+            visibility.set_if_neq(Visibility::Inherited);
+            if !has_icon {
+                commands.entity(entity).insert(MinimapIcon(color.color()));
+            }
+        } else {
+            visibility.set_if_neq(Visibility::Hidden);
+            if has_icon {
+                commands.entity(entity).remove::<MinimapIcon>();
+            }
+        }
+    }
+    for (gate, mut sprite) in &mut gates {
+        let raised = world_map
+            .at(gate.coordinate)
+            .is_some_and(|tile| tile.is_obstruction());
+        sprite.color = GATE_COLOR.with_alpha(if raised { 1.0 } else { 0.3 });
+    }
+}
@@ -0,0 +1,521 @@
+//! Levels are loaded at runtime from `.level.ron` files in `assets/levels` so
+//! maps can be edited without recompiling. With the `dev_native` feature the
+//! file is watched and the environment is rebuilt in place when it changes.
+//!
+//! Besides the map, a level places [`LevelMarker`]s that tell gameplay where
+//! things like the player and the finish line go.
+//!
+//! The campaign is the ordered list of levels in `levels/main.campaign.ron`.
+//! Finishing a level moves [`CampaignProgress`] on to the next one.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    asset_tracking::LoadResource,
+    gameplay::{
+        Environment,
+        collectible::CollectibleKind,
+        enemy::EnemyBehaviour,
+        environment::{
+            GroundType, KeyColor, ObstructionType, Tile, WorldMap, coordinate::Coordinate,
+        },
+        environment_mesh,
+        maze::{generator::MazeGenerator, seed::MazeSeed},
+        player::PLAYER_GIRTH,
+    },
+    screens::Screen,
+};
+use validate::validate_level;
+
+pub mod validate;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<MazeSource>();
+    app.init_resource::<CampaignProgress>();
+    app.init_resource::<CurrentLevel>();
+    app.init_asset::<Level>();
+    app.init_asset_loader::<LevelLoader>();
+    app.init_asset::<Campaign>();
+    app.init_asset_loader::<CampaignLoader>();
+    app.load_resource::<LevelAssets>();
+    app.add_systems(OnEnter(Screen::Gameplay), load_level);
+    app.add_systems(
+        Update,
+        reload_level.run_if(
+            in_state(Screen::Gameplay)
+                .and(resource_exists::<LevelAssets>)
+                .and(resource_equals(MazeSource::Campaign)),
+        ),
+    );
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct LevelAssets {
+    #[dependency]
+    campaign: Handle<Campaign>,
+}
+
+impl FromWorld for LevelAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            campaign: assets.load("levels/main.campaign.ron"),
+        }
+    }
+}
+
+impl LevelAssets {
+    /// The handle of the campaign level at `index`, if there is one.
+    pub(crate) fn campaign_level(
+        &self,
+        campaigns: &Assets<Campaign>,
+        index: usize,
+    ) -> Option<Handle<Level>> {
+        campaigns.get(&self.campaign)?.levels.get(index).cloned()
+    }
+
+    /// How many levels the campaign has.
+    pub(crate) fn campaign_length(&self, campaigns: &Assets<Campaign>) -> usize {
+        campaigns
+            .get(&self.campaign)
+            .map_or(0, |campaign| campaign.levels.len())
+    }
+}
+
+/// Where the maze for the next run of gameplay comes from.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum MazeSource {
+    /// The current level of the campaign, see [`CampaignProgress`].
+    #[default]
+    Campaign,
+    /// A maze generated from the [`MazeSeed`].
+    Generated,
+}
+
+/// How far through the campaign the player is.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct CampaignProgress {
+    /// Index of the level being played (or about to be played).
+    pub level: usize,
+}
+
+/// The handcrafted levels, in the order they are played.
+#[derive(Asset, Reflect, Debug, Clone)]
+pub(crate) struct Campaign {
+    #[dependency]
+    pub levels: Vec<Handle<Level>>,
+}
+
+/// Everything a level file describes.
+#[derive(Asset, Reflect, Debug, Clone, Default)]
+pub(crate) struct Level {
+    pub name: String,
+    /// Played instead of the default gameplay music.
+    #[dependency]
+    pub music: Option<Handle<AudioSource>>,
+    /// How far the player can see, in tiles. Without one, sight is only
+    /// limited by what's in the way.
+    pub torch_radius: Option<f32>,
+    /// Whether the finish line only counts once every collectible is picked up.
+    pub require_all_collectibles: bool,
+    /// How many times enemies can catch the player before the level starts
+    /// over. Without lives, being caught only sends the player back to the start.
+    pub lives: Option<u32>,
+    pub world_map: WorldMap,
+    pub markers: LevelMarkers,
+}
+
+/// Something a level places in the world. Positions are in world array space,
+/// i.e. tiles from the top left of the map.
+#[derive(Deserialize, Reflect, Debug, Clone, PartialEq)]
+pub(crate) enum LevelMarker {
+    /// Where the player appears. Every level needs exactly one.
+    PlayerStart { x: f32, y: f32 },
+    /// The player wins by standing entirely within this block of tiles.
+    FinishZone {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+    /// A key the player picks up by walking over it. It opens one door of the
+    /// same color.
+    Key { x: i32, y: i32, color: KeyColor },
+    /// Flipped with the interact action. Each flip toggles the gates at the
+    /// listed tiles.
+    Lever {
+        x: i32,
+        y: i32,
+        gates: Vec<(i32, i32)>,
+    },
+    /// Toggles the gates at the listed tiles while anything stands on it.
+    PressurePlate {
+        x: i32,
+        y: i32,
+        gates: Vec<(i32, i32)>,
+    },
+    /// A coin, picked up for points by walking over it.
+    Coin { x: i32, y: i32 },
+    /// A gem. Worth more than a coin.
+    Gem { x: i32, y: i32 },
+    /// An enemy, which catches the player by touching them.
+    Enemy {
+        x: f32,
+        y: f32,
+        behaviour: EnemyBehaviour,
+        /// How far away the enemy can spot the player from, in tiles. Enemies
+        /// that spot the player give chase. Without one, they never do.
+        #[serde(default)]
+        sight: Option<f32>,
+    },
+}
+
+/// The level being played, as it was loaded. Its map is copied into the
+/// [`WorldMap`] resource, which is the one to read since doors and gates
+/// change it during play.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct CurrentLevel(pub Level);
+
+/// The markers of a level.
+#[derive(Reflect, Debug, Clone, Default)]
+pub(crate) struct LevelMarkers(pub Vec<LevelMarker>);
+
+impl LevelMarkers {
+    /// Where the player appears, in world array space.
+    pub fn player_start(&self) -> Vec2 {
+        self.0
+            .iter()
+            .find_map(|marker| match marker {
+                LevelMarker::PlayerStart { x, y } => Some(Vec2::new(*x, *y)),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Where every collectible is, and what it is.
+    pub fn collectibles(&self) -> impl Iterator<Item = (Coordinate, CollectibleKind)> {
+        self.0.iter().filter_map(|marker| match *marker {
+            LevelMarker::Coin { x, y } => Some((Coordinate::new(x, y), CollectibleKind::Coin)),
+            LevelMarker::Gem { x, y } => Some((Coordinate::new(x, y), CollectibleKind::Gem)),
+            _ => None,
+        })
+    }
+
+    /// The corners (min, max) of every finish zone, in world array space.
+    pub fn finish_zones(&self) -> impl Iterator<Item = (Coordinate, Coordinate)> {
+        self.0.iter().filter_map(|marker| match *marker {
+            LevelMarker::FinishZone {
+                x,
+                y,
+                width,
+                height,
+            } => Some((
+                Coordinate::new(x, y),
+                Coordinate::new(x + width as i32, y + height as i32),
+            )),
+            _ => None,
+        })
+    }
+}
+
+/// The on-disk layout of a level. Each layer is a list of rows drawn with one
+/// character per tile; see the legend in the level files for the symbols. Maps
+/// can be any size as long as every row is the same length and both layers match.
+#[derive(Deserialize)]
+struct LevelFile {
+    name: String,
+    /// Path to the level's music, relative to the assets folder.
+    #[serde(default)]
+    music: Option<String>,
+    /// How far the player can see, in tiles.
+    #[serde(default)]
+    torch_radius: Option<f32>,
+    /// Whether every collectible must be picked up before the finish line counts.
+    #[serde(default)]
+    require_all_collectibles: bool,
+    /// How many times the player can be caught before the level starts over.
+    #[serde(default)]
+    lives: Option<u32>,
+    ground: Vec<String>,
+    obstructions: Vec<String>,
+    markers: Vec<LevelMarker>,
+}
+
+#[derive(Debug, Error)]
+pub enum LevelLoaderError {
+    #[error("could not read level file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse level file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("the {layer} layer is empty")]
+    Empty { layer: &'static str },
+    #[error("row {y} of the {layer} layer is {found} tiles wide, expected {expected}")]
+    RaggedRow {
+        layer: &'static str,
+        y: usize,
+        found: usize,
+        expected: usize,
+    },
+    #[error("the ground and obstruction layers have different dimensions")]
+    LayerMismatch,
+    #[error("expected exactly one PlayerStart marker, found {0}")]
+    PlayerStartCount(usize),
+    #[error("unknown {layer} symbol '{symbol}' at ({x}, {y})")]
+    UnknownSymbol {
+        layer: &'static str,
+        symbol: char,
+        x: usize,
+        y: usize,
+    },
+}
+
+#[derive(Default)]
+struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let (level, world_map) = parse_level_file(&bytes)?;
+        Ok(Level {
+            name: level.name,
+            music: level.music.map(|path| load_context.load(path)),
+            torch_radius: level.torch_radius,
+            require_all_collectibles: level.require_all_collectibles,
+            lives: level.lives,
+            world_map,
+            markers: LevelMarkers(level.markers),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+/// The on-disk layout of a campaign: paths to level files, relative to the
+/// assets folder.
+#[derive(Deserialize)]
+struct CampaignFile {
+    levels: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum CampaignLoaderError {
+    #[error("could not read campaign file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse campaign file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("the campaign has no levels")]
+    Empty,
+}
+
+#[derive(Default)]
+struct CampaignLoader;
+
+impl AssetLoader for CampaignLoader {
+    type Asset = Campaign;
+    type Settings = ();
+    type Error = CampaignLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let campaign: CampaignFile = ron::de::from_bytes(&bytes)?;
+        if campaign.levels.is_empty() {
+            return Err(CampaignLoaderError::Empty);
+        }
+        Ok(Campaign {
+            levels: campaign
+                .levels
+                .into_iter()
+                .map(|path| load_context.load(path))
+                .collect(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["campaign.ron"]
+    }
+}
+
+/// Parse a level file and build its map. Loading its music is left to the
+/// caller, since that needs the asset server.
+fn parse_level_file(bytes: &[u8]) -> Result<(LevelFile, WorldMap), LevelLoaderError> {
+    let level: LevelFile = ron::de::from_bytes(bytes)?;
+    let player_starts = level
+        .markers
+        .iter()
+        .filter(|marker| matches!(marker, LevelMarker::PlayerStart { .. }))
+        .count();
+    if player_starts != 1 {
+        return Err(LevelLoaderError::PlayerStartCount(player_starts));
+    }
+    let world_map = world_map_from_level_file(&level)?;
+    Ok((level, world_map))
+}
+
+fn world_map_from_level_file(level: &LevelFile) -> Result<WorldMap, LevelLoaderError> {
+    let ground = parse_layer(&level.ground, "ground", GroundType::from_symbol)?;
+    let obstructions = parse_layer(
+        &level.obstructions,
+        "obstruction",
+        ObstructionType::from_symbol,
+    )?;
+    if ground.dimensions != obstructions.dimensions {
+        return Err(LevelLoaderError::LayerMismatch);
+    }
+
+    let tiles = ground
+        .tiles
+        .into_iter()
+        .zip(obstructions.tiles)
+        .map(|(ground, obstruction)| Tile::new(ground, obstruction))
+        .collect();
+    Ok(WorldMap::new(
+        ground.dimensions.x,
+        ground.dimensions.y,
+        tiles,
+    ))
+}
+
+/// One parsed layer, flattened row by row.
+struct Layer<T> {
+    dimensions: UVec2,
+    tiles: Vec<T>,
+}
+
+fn parse_layer<T>(
+    rows: &[String],
+    layer: &'static str,
+    from_symbol: fn(char) -> Option<T>,
+) -> Result<Layer<T>, LevelLoaderError> {
+    let width = rows
+        .first()
+        .map(|row| row.chars().count())
+        .filter(|&width| width > 0)
+        .ok_or(LevelLoaderError::Empty { layer })?;
+
+    let mut tiles = Vec::with_capacity(width * rows.len());
+    for (y, row) in rows.iter().enumerate() {
+        let found = row.chars().count();
+        if found != width {
+            return Err(LevelLoaderError::RaggedRow {
+                layer,
+                y,
+                found,
+                expected: width,
+            });
+        }
+        for (x, symbol) in row.chars().enumerate() {
+            tiles.push(from_symbol(symbol).ok_or(LevelLoaderError::UnknownSymbol {
+                layer,
+                symbol,
+                x,
+                y,
+            })?);
+        }
+    }
+    Ok(Layer {
+        dimensions: UVec2::new(width as u32, rows.len() as u32),
+        tiles,
+    })
+}
+
+/// Make the current level the [`CurrentLevel`], and copy its map into the
+/// [`WorldMap`] resource. Systems that read either when entering gameplay
+/// should run `.after(load_level)`.
+pub(crate) fn load_level(
+    mut world_map: ResMut<WorldMap>,
+    mut current_level: ResMut<CurrentLevel>,
+    maze_source: Res<MazeSource>,
+    maze_seed: Res<MazeSeed>,
+    campaign_progress: Res<CampaignProgress>,
+    level_assets: Res<LevelAssets>,
+    campaigns: Res<Assets<Campaign>>,
+    levels: Res<Assets<Level>>,
+) {
+    let level = match *maze_source {
+        MazeSource::Campaign => level_assets
+            .campaign_level(&campaigns, campaign_progress.level)
+            .and_then(|handle| levels.get(&handle))
+            .cloned(),
+        MazeSource::Generated => {
+            let generator = MazeGenerator::from_seed(maze_seed.value);
+            info!("Generating maze: {generator:?}");
+            let mut level = generator.generate();
+            level.name = maze_seed.label();
+            Some(level)
+        }
+    };
+    if let Some(level) = level {
+        *world_map = level.world_map.clone();
+        current_level.0 = level;
+        let level = &current_level.0;
+        let issues = validate_level(
+            &world_map,
+            &level.markers,
+            level.require_all_collectibles,
+            PLAYER_GIRTH,
+        );
+        for issue in issues {
+            warn!("{}: {issue}", level.name);
+        }
+    }
+}
+
+/// Swap in the new map and rebuild the environment mesh when the level file
+/// is hot reloaded. Doors and gates keep their current state, and changes to
+/// them, like markers, only take effect the next time the level starts.
+fn reload_level(
+    mut asset_events: MessageReader<AssetEvent<Level>>,
+    level_assets: Res<LevelAssets>,
+    campaign_progress: Res<CampaignProgress>,
+    campaigns: Res<Assets<Campaign>>,
+    levels: Res<Assets<Level>>,
+    mut world_map: ResMut<WorldMap>,
+    mut environment: Single<&mut Mesh2d, With<Environment>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Some(level_id) = level_assets
+        .campaign_level(&campaigns, campaign_progress.level)
+        .map(|handle| handle.id())
+    else {
+        return;
+    };
+    let modified = asset_events.read().any(|event| event.is_modified(level_id));
+    if !modified {
+        return;
+    }
+    let Some(level) = levels.get(level_id) else {
+        return;
+    };
+
+    info!("Level reloaded, rebuilding the environment.");
+    let mut reloaded = level.world_map.clone();
+    reloaded.keep_switchables_from(&world_map);
+    *world_map = reloaded;
+    environment.0 = environment_mesh(&world_map, &mut meshes);
+}
@@ -0,0 +1,593 @@
+//! Check that a level can be completed, and point out anything else that looks
+//! like a mistake in it. Every level is checked as it is loaded, and with the
+//! `dev` feature level files can be checked from the command line:
+//!
+//! ```text
+//! cargo run -- lint-levels assets/levels/*.level.ron
+//! ```
+
+use std::{collections::VecDeque, path::Path};
+
+use bevy::{platform::collections::HashSet, prelude::*};
+use thiserror::Error;
+
+use crate::gameplay::{
+    TILE_DIM,
+    enemy::{ENEMY_GIRTH, EnemyBehaviour},
+    environment::{KeyColor, ObstructionType, WorldMap, coordinate::Coordinate},
+    level::{LevelLoaderError, LevelMarker, LevelMarkers, parse_level_file},
+    pathfinding::{STEPS, distances_from, fits, footprint, footprints_within},
+    player::PLAYER_GIRTH,
+    utils::hitbox::Hitbox,
+};
+
+/// Something wrong with a level. Errors make it impossible to complete, the
+/// rest are just suspicious; see [`LevelIssue::is_error`].
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum LevelIssue {
+    #[error("the player starts inside an obstruction at ({}, {})", .0.x, .0.y)]
+    StartBlocked(Vec2),
+    #[error("there is no finish zone")]
+    NoFinish,
+    #[error("no finish zone is big enough to hold the player")]
+    FinishTooSmall,
+    #[error("no finish zone can be reached from the player start")]
+    FinishUnreachable,
+    #[error("{tiles} open tiles around ({}, {}) can't be reached", .example.x, .example.y)]
+    UnreachableArea { example: Coordinate, tiles: usize },
+    #[error("the open tile at ({}, {}) is walled in on every side", .0.x, .0.y)]
+    OrphanTile(Coordinate),
+    #[error("the tile at ({}, {}) has no sprite for its ground or obstruction", .0.x, .0.y)]
+    MissingSprite(Coordinate),
+    #[error("the collectible at ({}, {}) can't be reached", .at.x, .at.y)]
+    CollectibleUnreachable { at: Coordinate, required: bool },
+    #[error("no key opens the {} door at ({}, {})", .color.name(), .door.x, .door.y)]
+    NoKeyForDoor { door: Coordinate, color: KeyColor },
+    #[error("the switch at ({}, {}) toggles ({}, {}), which isn't a gate", .switch.x, .switch.y, .target.x, .target.y)]
+    SwitchWithoutGate {
+        switch: Coordinate,
+        target: Coordinate,
+    },
+    #[error("an enemy starts inside an obstruction at ({}, {})", .0.x, .0.y)]
+    EnemyBlocked(Vec2),
+    #[error("an enemy patrols to ({}, {}), which it can't stand on", .0.x, .0.y)]
+    WaypointBlocked(Coordinate),
+}
+
+impl LevelIssue {
+    /// Whether the issue makes the level impossible to complete.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Self::StartBlocked(_)
+                | Self::NoFinish
+                | Self::FinishTooSmall
+                | Self::FinishUnreachable
+                | Self::CollectibleUnreachable { required: true, .. }
+        )
+    }
+}
+
+/// Where things are in a level, in world array space.
+#[derive(Debug, Default)]
+pub struct Layout<'a> {
+    /// Where the player starts.
+    pub start: Vec2,
+    pub finishes: &'a [Hitbox],
+    pub collectibles: &'a [Coordinate],
+    pub keys: &'a [(Coordinate, KeyColor)],
+    /// Every lever, and the gates it toggles.
+    pub levers: &'a [(Coordinate, Vec<Coordinate>)],
+}
+
+/// Check that a player with `girth` can get from the start of the `layout`
+/// entirely inside one of its finishes, and look for open tiles that can't be
+/// reached and tiles that can't be drawn. Every collectible should be
+/// reachable too, and must be when `all_required`.
+///
+/// Doors and gates count as they would in play: a door opens once the key for
+/// it has been picked up, and a gate is up or down depending on the levers
+/// flipped so far. Pressure plates need something standing on them, so the
+/// gates they toggle are left as they start. Levels with too many combinations
+/// of keys, doors and levers to try are checked with everything open instead.
+pub fn validate(
+    world_map: &WorldMap,
+    layout: &Layout,
+    all_required: bool,
+    girth: Vec2,
+) -> Vec<LevelIssue> {
+    let mut issues = Vec::new();
+
+    for (coordinate, tile) in world_map.iter() {
+        if tile.is_missing_sprite() {
+            issues.push(LevelIssue::MissingSprite(coordinate));
+        }
+    }
+
+    let start = layout.start;
+    let half_girth = girth / 2.0;
+    let start_hitbox = Hitbox::from_corners(start - half_girth, start + half_girth);
+    let start_blocked = world_map.iter().any(|(coordinate, tile)| {
+        tile.is_obstruction() && tile.hitbox(coordinate).intersects(&start_hitbox)
+    });
+    if start_blocked {
+        issues.push(LevelIssue::StartBlocked(start));
+    }
+
+    // Paths are planned for the top left tile of the player's footprint.
+    let footprint = footprint(Some(girth));
+    let start_tile = Coordinate::from(start - Vec2::splat((footprint - 1) as f32 * TILE_DIM / 2.0));
+    let reachable = explore(world_map, layout, start_tile, girth);
+
+    let big_enough = layout
+        .finishes
+        .iter()
+        .filter(|finish| finish.width() >= girth.x && finish.height() >= girth.y)
+        .collect::<Vec<_>>();
+    if layout.finishes.is_empty() {
+        issues.push(LevelIssue::NoFinish);
+    } else if big_enough.is_empty() {
+        issues.push(LevelIssue::FinishTooSmall);
+    } else if !start_blocked
+        && !big_enough.iter().any(|finish| {
+            footprints_within(finish, footprint).any(|tile| reachable.contains(&tile))
+        })
+    {
+        issues.push(LevelIssue::FinishUnreachable);
+    }
+
+    if !start_blocked {
+        for &at in layout.collectibles {
+            if !covered(|tile| reachable.contains(tile), at, footprint) {
+                issues.push(LevelIssue::CollectibleUnreachable {
+                    at,
+                    required: all_required,
+                });
+            }
+        }
+    }
+
+    // Group the open tiles the player can't reach into areas. Doors that never
+    // open count as open tiles here, so they turn up too.
+    if !start_blocked {
+        let open_map = world_map.with_everything_open();
+        let mut unreached = world_map
+            .iter()
+            .filter(|(coordinate, _)| fits(&open_map, *coordinate, 1))
+            .map(|(coordinate, _)| coordinate)
+            .filter(|coordinate| !reachable.contains(coordinate))
+            .collect::<Vec<_>>();
+        while let Some(&example) = unreached.first() {
+            let area = distances_from(&open_map, example, None);
+            unreached.retain(|coordinate| !area.contains_key(coordinate));
+            issues.push(if area.len() == 1 {
+                LevelIssue::OrphanTile(example)
+            } else {
+                LevelIssue::UnreachableArea {
+                    example,
+                    tiles: area.len(),
+                }
+            });
+        }
+    }
+
+    issues
+}
+
+/// How far [`explore`] goes, in combinations of keys, doors and levers,
+/// before giving up and opening everything.
+const MAX_EXPLORED_STATES: usize = 4096;
+
+/// Where the player is, and what they've done so far, while exploring a level.
+/// Each set is a bitmask over the keys, doors or levers of the level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Progress {
+    at: Coordinate,
+    keys_picked_up: u64,
+    doors_opened: u64,
+    levers_flipped: u64,
+}
+
+/// Every tile the top left of the player's footprint can get to from `start`,
+/// picking up keys, opening doors and flipping levers along the way.
+fn explore(
+    world_map: &WorldMap,
+    layout: &Layout,
+    start: Coordinate,
+    girth: Vec2,
+) -> HashSet<Coordinate> {
+    let footprint = footprint(Some(girth));
+    let doors = world_map
+        .iter()
+        .filter_map(|(coordinate, tile)| match tile.obstruction() {
+            ObstructionType::Door(color) => Some((coordinate, color)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    // When there's too much to keep track of, settle for what could be reached
+    // with everything open. That can miss a level that can't be completed, but
+    // never fails one that can.
+    let everything_open = || {
+        distances_from(&world_map.with_everything_open(), start, Some(girth))
+            .into_keys()
+            .collect()
+    };
+    if [layout.keys.len(), doors.len(), layout.levers.len()]
+        .iter()
+        .any(|count| *count > u64::BITS as usize)
+    {
+        return everything_open();
+    }
+
+    let mut reachable = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut frontier = VecDeque::from([Progress {
+        at: start,
+        keys_picked_up: 0,
+        doors_opened: 0,
+        levers_flipped: 0,
+    }]);
+    while let Some(mut progress) = frontier.pop_front() {
+        if seen.contains(&progress) {
+            continue;
+        }
+        if seen.len() >= MAX_EXPLORED_STATES {
+            return everything_open();
+        }
+        seen.insert(progress);
+        let map = progress_map(world_map, layout, &doors, progress);
+        let area = distances_from(&map, progress.at, Some(girth));
+        reachable.extend(area.keys().copied());
+
+        // Picking up keys never makes things worse, so pick up every one in
+        // reach straight away.
+        for (index, (at, _)) in layout.keys.iter().enumerate() {
+            if covered(|tile| area.contains_key(tile), *at, footprint) {
+                progress.keys_picked_up |= 1 << index;
+            }
+        }
+
+        for (index, (door, color)) in doors.iter().enumerate() {
+            if progress.doors_opened & (1 << index) != 0
+                || keys_held(layout, &doors, progress, *color) == 0
+            {
+                continue;
+            }
+            let within_reach = STEPS.iter().any(|step| {
+                let next_to = Coordinate::new(door.x + step.x, door.y + step.y);
+                covered(|tile| area.contains_key(tile), next_to, footprint)
+            });
+            if within_reach {
+                frontier.push_back(Progress {
+                    doors_opened: progress.doors_opened | 1 << index,
+                    ..progress
+                });
+            }
+        }
+
+        for (index, (lever, _)) in layout.levers.iter().enumerate() {
+            // Stand on the lever to flip it, since the gates it moves might
+            // shut the player in there.
+            let standing_on = (0..footprint)
+                .flat_map(|dy| (0..footprint).map(move |dx| (dx, dy)))
+                .map(|(dx, dy)| Coordinate::new(lever.x - dx, lever.y - dy))
+                .find(|at| area.contains_key(at));
+            if let Some(at) = standing_on {
+                frontier.push_back(Progress {
+                    at,
+                    levers_flipped: progress.levers_flipped ^ 1 << index,
+                    ..progress
+                });
+            }
+        }
+    }
+    reachable
+}
+
+/// How many keys of `color` the player is carrying.
+fn keys_held(
+    layout: &Layout,
+    doors: &[(Coordinate, KeyColor)],
+    progress: Progress,
+    color: KeyColor,
+) -> usize {
+    let picked_up = layout
+        .keys
+        .iter()
+        .enumerate()
+        .filter(|(index, (_, key))| progress.keys_picked_up & (1 << index) != 0 && *key == color)
+        .count();
+    let used = doors
+        .iter()
+        .enumerate()
+        .filter(|(index, (_, door))| progress.doors_opened & (1 << index) != 0 && *door == color)
+        .count();
+    picked_up.saturating_sub(used)
+}
+
+/// The map with the doors opened and the gates moved by the levers flipped.
+fn progress_map(
+    world_map: &WorldMap,
+    layout: &Layout,
+    doors: &[(Coordinate, KeyColor)],
+    progress: Progress,
+) -> WorldMap {
+    let mut map = world_map.clone();
+    for (index, (door, color)) in doors.iter().enumerate() {
+        if progress.doors_opened & (1 << index) != 0
+            && let Some(tile) = map.at_mut(*door)
+        {
+            tile.set_obstruction(ObstructionType::OpenDoor(*color));
+        }
+    }
+    for (index, (_, gates)) in layout.levers.iter().enumerate() {
+        if progress.levers_flipped & (1 << index) == 0 {
+            continue;
+        }
+        for gate in gates {
+            let Some(tile) = map.at_mut(*gate) else {
+                continue;
+            };
+            match tile.obstruction() {
+                ObstructionType::Gate => tile.set_obstruction(ObstructionType::OpenGate),
+                ObstructionType::OpenGate => tile.set_obstruction(ObstructionType::Gate),
+                _ => {}
+            }
+        }
+    }
+    map
+}
+
+/// Whether a footprint with its top left on a `reachable` tile covers the tile
+/// `at`.
+fn covered(reachable: impl Fn(&Coordinate) -> bool, at: Coordinate, footprint: i32) -> bool {
+    (0..footprint)
+        .any(|dy| (0..footprint).any(|dx| reachable(&Coordinate::new(at.x - dx, at.y - dy))))
+}
+
+/// Check a level using its markers, for the player. Also checks that every
+/// door has a key, every switch is wired to gates and every enemy has room to
+/// move.
+pub fn validate_level(
+    world_map: &WorldMap,
+    level_markers: &LevelMarkers,
+    require_all_collectibles: bool,
+    girth: Vec2,
+) -> Vec<LevelIssue> {
+    let finishes = level_markers
+        .finish_zones()
+        .map(|(min, max)| Hitbox::from_corners(min.to_vec2(), max.to_vec2()))
+        .collect::<Vec<_>>();
+    let collectibles = level_markers
+        .collectibles()
+        .map(|(coordinate, _)| coordinate)
+        .collect::<Vec<_>>();
+    let keys = level_markers
+        .0
+        .iter()
+        .filter_map(|marker| match marker {
+            LevelMarker::Key { x, y, color } => Some((Coordinate::new(*x, *y), *color)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let levers = level_markers
+        .0
+        .iter()
+        .filter_map(|marker| match marker {
+            LevelMarker::Lever { x, y, gates } => Some((
+                Coordinate::new(*x, *y),
+                gates.iter().map(|&(x, y)| Coordinate::new(x, y)).collect(),
+            )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let layout = Layout {
+        start: level_markers.player_start(),
+        finishes: &finishes,
+        collectibles: &collectibles,
+        keys: &keys,
+        levers: &levers,
+    };
+    let mut issues = validate(world_map, &layout, require_all_collectibles, girth);
+
+    for (door, tile) in world_map.iter() {
+        if let ObstructionType::Door(color) = tile.obstruction() {
+            let has_key = level_markers.0.iter().any(
+                |marker| matches!(marker, LevelMarker::Key { color: key, .. } if *key == color),
+            );
+            if !has_key {
+                issues.push(LevelIssue::NoKeyForDoor { door, color });
+            }
+        }
+    }
+
+    for marker in &level_markers.0 {
+        let (LevelMarker::Lever { x, y, gates } | LevelMarker::PressurePlate { x, y, gates }) =
+            marker
+        else {
+            continue;
+        };
+        for &(gate_x, gate_y) in gates {
+            let target = Coordinate::new(gate_x, gate_y);
+            let is_gate = world_map.at(target).is_some_and(|tile| {
+                matches!(
+                    tile.obstruction(),
+                    ObstructionType::Gate | ObstructionType::OpenGate
+                )
+            });
+            if !is_gate {
+                issues.push(LevelIssue::SwitchWithoutGate {
+                    switch: Coordinate::new(*x, *y),
+                    target,
+                });
+            }
+        }
+    }
+
+    let enemy_footprint = footprint(Some(ENEMY_GIRTH));
+    for marker in &level_markers.0 {
+        let LevelMarker::Enemy {
+            x, y, behaviour, ..
+        } = marker
+        else {
+            continue;
+        };
+        let at = Vec2::new(*x, *y) * TILE_DIM;
+        let half_girth = ENEMY_GIRTH / 2.0;
+        let hitbox = Hitbox::from_corners(at - half_girth, at + half_girth);
+        let blocked = world_map.iter().any(|(coordinate, tile)| {
+            tile.is_obstruction() && tile.hitbox(coordinate).intersects(&hitbox)
+        });
+        if blocked {
+            issues.push(LevelIssue::EnemyBlocked(at));
+        }
+        if let EnemyBehaviour::Patrol(waypoints) = behaviour {
+            for &(waypoint_x, waypoint_y) in waypoints {
+                let waypoint = Coordinate::new(waypoint_x, waypoint_y);
+                if !fits(world_map, waypoint, enemy_footprint) {
+                    issues.push(LevelIssue::WaypointBlocked(waypoint));
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Load a level file from disk and check it for the player.
+pub fn lint_level_file(path: &Path) -> Result<Vec<LevelIssue>, LevelLoaderError> {
+    let bytes = std::fs::read(path)?;
+    let (level, world_map) = parse_level_file(&bytes)?;
+    let level_markers = LevelMarkers(level.markers);
+    Ok(validate_level(
+        &world_map,
+        &level_markers,
+        level.require_all_collectibles,
+        PLAYER_GIRTH,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_levels_can_be_completed() {
+        let levels = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/levels");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(levels).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.to_string_lossy().ends_with(".level.ron") {
+                continue;
+            }
+            let issues = lint_level_file(&path).unwrap();
+            let errors = issues
+                .iter()
+                .filter(|issue| issue.is_error())
+                .collect::<Vec<_>>();
+            assert!(errors.is_empty(), "{}: {errors:?}", path.display());
+            checked += 1;
+        }
+        assert!(checked > 0, "no levels found");
+    }
+
+    /// Check a corridor level running left to right, starting at the left end
+    /// and finishing at the right.
+    fn corridor_issues(corridor: &str, markers: &str) -> Vec<LevelIssue> {
+        let wall = "-".repeat(corridor.len());
+        let ground = "g".repeat(corridor.len() + 2);
+        let level = format!(
+            r#"(
+                name: "Corridor",
+                ground: ["{ground}", "{ground}", "{ground}"],
+                obstructions: ["+{wall}+", "|{corridor}|", "+{wall}+"],
+                markers: [
+                    PlayerStart(x: 1.5, y: 1.5),
+                    FinishZone(x: {}, y: 1, width: 1, height: 1),
+                    {markers}
+                ],
+            )"#,
+            corridor.len()
+        );
+        let (level, world_map) = parse_level_file(level.as_bytes()).unwrap();
+        validate_level(
+            &world_map,
+            &LevelMarkers(level.markers),
+            false,
+            PLAYER_GIRTH,
+        )
+    }
+
+    #[test]
+    fn doors_open_with_a_key_from_their_side() {
+        let issues = corridor_issues("..R..", "Key(x: 2, y: 1, color: Red)");
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn a_key_behind_its_own_door_is_no_use() {
+        let issues = corridor_issues("..R..", "Key(x: 4, y: 1, color: Red)");
+        assert!(
+            issues.contains(&LevelIssue::FinishUnreachable),
+            "{issues:?}"
+        );
+    }
+
+    #[test]
+    fn keys_are_used_up() {
+        let issues = corridor_issues(
+            ".R.R.",
+            "Key(x: 1, y: 1, color: Red), Key(x: 2, y: 1, color: Red)",
+        );
+        assert!(issues.is_empty(), "{issues:?}");
+        let issues = corridor_issues(".R.R.", "Key(x: 1, y: 1, color: Red)");
+        assert!(
+            issues.contains(&LevelIssue::FinishUnreachable),
+            "{issues:?}"
+        );
+    }
+
+    #[test]
+    fn gates_open_with_a_lever_from_their_side() {
+        let issues = corridor_issues("..#..", "Lever(x: 1, y: 1, gates: [(3, 1)])");
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn a_lever_behind_its_own_gate_is_no_use() {
+        let issues = corridor_issues("..#..", "Lever(x: 4, y: 1, gates: [(3, 1)])");
+        assert!(
+            issues.contains(&LevelIssue::FinishUnreachable),
+            "{issues:?}"
+        );
+    }
+
+    #[test]
+    fn levels_with_too_many_combinations_are_checked_with_everything_open() {
+        // A dozen levers that do nothing, then a dozen gates each opened by the
+        // lever in front of it. Too many combinations to try them all before
+        // reaching the last gate.
+        let mut corridor = ".".repeat(12);
+        let mut markers = (1..=12)
+            .map(|x| format!("Lever(x: {x}, y: 1, gates: []),"))
+            .collect::<String>();
+        for _ in 0..12 {
+            let x = corridor.len() + 1;
+            markers += &format!("Lever(x: {x}, y: 1, gates: [({}, 1)]),", x + 1);
+            corridor += ".#";
+        }
+        corridor += ".";
+        let issues = corridor_issues(&corridor, &markers);
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn a_lever_can_shut_the_way_out() {
+        // Flipping the lever opens the first gate and shuts the second.
+        let issues = corridor_issues("..#._..", "Lever(x: 1, y: 1, gates: [(3, 1), (5, 1)])");
+        assert!(
+            issues.contains(&LevelIssue::FinishUnreachable),
+            "{issues:?}"
+        );
+    }
+}
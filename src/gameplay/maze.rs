@@ -4,9 +4,14 @@ use crate::{
     AppSystems, PausableSystems,
     gameplay::{
         SpriteSheet, TILE_DIM,
-        environment::coordinate::Coordinate,
+        dialogue::{DialogueAssets, DialogueScript, dialogue_open, start_dialogue},
+        environment::WorldMap,
+        level::{CurrentLevel, load_level},
+        maze::seed::MazeSeed,
+        minimap::MinimapIcon,
         movement::MovementController,
-        player::Player,
+        player::{Player, send_player_to_start},
+        stats::RunStats,
         utils::{
             hitbox::Hitbox, render_position_from_world_array_position,
             world_array_position_from_render_position,
@@ -16,22 +21,58 @@ use crate::{
     utils::Z,
 };
 
+pub mod generator;
+pub mod seed;
+
+/// #4fb360, how finish lines are marked on the minimap.
+const FINISH_MINIMAP_COLOR: Color = Color::srgb(0.310, 0.702, 0.376);
+
 pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<MazeSeed>();
     app.init_state::<MazeProgress>();
+    app.add_message::<RestartLevel>();
 
-    app.add_systems(OnEnter(Screen::Gameplay), (init_maze, spawn_finish_line));
-    app.add_systems(OnEnter(MazeProgress::Start), start_seeking); // TODO: Add some intro dialogue
-    // app.add_systems(OnEnter(MazeProgress::Finish), win_dialogue); // TODO: Add some finish dialogue
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (init_maze, spawn_finish_line.after(load_level)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_maze);
+    app.add_systems(OnEnter(MazeProgress::Start), intro_dialogue);
+    app.add_systems(OnEnter(MazeProgress::Finish), win_dialogue);
+    // Wait for the dialogue to be dismissed before moving on.
+    app.add_systems(
+        Update,
+        (
+            start_seeking.run_if(in_state(MazeProgress::Start)),
+            complete_level.run_if(in_state(MazeProgress::Finish)),
+        )
+            .run_if(not(dialogue_open))
+            .in_set(AppSystems::Update),
+    );
     app.add_systems(
         Update,
         detect_finish_line_crossing
+            .run_if(in_state(MazeProgress::Seeking))
             .in_set(AppSystems::Update)
             .in_set(PausableSystems),
     );
+    app.add_systems(
+        Update,
+        restart_level
+            .run_if(in_state(Screen::Gameplay).and(on_message::<RestartLevel>))
+            .in_set(AppSystems::Update),
+    );
 }
 
+/// Start the level being played over from the top, without going back
+/// through the intro or stopping the clock. Everything in the maze goes back
+/// to how it started: the player here, and enemies, keys, doors, gates and
+/// collectibles in their own modules.
+#[derive(Message, Debug)]
+pub(crate) struct RestartLevel;
+
 #[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
-enum MazeProgress {
+pub(crate) enum MazeProgress {
     #[default]
     None,
     Start,
@@ -41,68 +82,122 @@ enum MazeProgress {
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-struct FinishLine {
-    hitbox: Hitbox,
+pub(crate) struct FinishLine {
+    pub hitbox: Hitbox,
 }
 
 fn init_maze(mut next_maze_progress: ResMut<NextState<MazeProgress>>) {
     next_maze_progress.set(MazeProgress::Start)
 }
 
+fn reset_maze(mut next_maze_progress: ResMut<NextState<MazeProgress>>) {
+    next_maze_progress.set(MazeProgress::None)
+}
+
+fn restart_level(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+    mut run_stats: ResMut<RunStats>,
+    player: Single<(Entity, &mut Transform), With<Player>>,
+) {
+    let (entity, mut transform) = player.into_inner();
+    send_player_to_start(
+        &mut commands,
+        (entity, &mut transform),
+        &current_level.0.markers,
+        world_map.dimensions(),
+        &mut run_stats,
+    );
+}
+
+fn intro_dialogue(
+    mut commands: Commands,
+    dialogue_assets: Res<DialogueAssets>,
+    scripts: Res<Assets<DialogueScript>>,
+) {
+    start_dialogue(&mut commands, scripts.get(&dialogue_assets.intro));
+}
+
+fn win_dialogue(
+    mut commands: Commands,
+    dialogue_assets: Res<DialogueAssets>,
+    scripts: Res<Assets<DialogueScript>>,
+) {
+    start_dialogue(&mut commands, scripts.get(&dialogue_assets.win));
+}
+
 fn start_seeking(mut next_maze_progress: ResMut<NextState<MazeProgress>>) {
     next_maze_progress.set(MazeProgress::Seeking)
 }
 
-fn spawn_finish_line(mut commands: Commands, sheet: Res<SpriteSheet>) {
-    // from coordinate 10, 1 to coordinate 10, 2 and covering those tiles (+1, +1)
-    let corners = (
-        Coordinate::new(10, 1).to_vec2(),
-        Coordinate::new(10 + 1, 2 + 1).to_vec2(),
-    );
-    commands.spawn((
-        Name::new("Finish Line"),
-        FinishLine {
-            hitbox: Hitbox::from_corners(corners.0, corners.1),
-        },
-        Sprite {
-            image: sheet.texture.clone(),
-            texture_atlas: Some(TextureAtlas {
-                layout: sheet.layout.clone(),
-                index: 5,
-            }),
-            custom_size: Some(Vec2::new(1.0, 2.0) / crate::gameplay::SCALE_FACTOR),
-            image_mode: SpriteImageMode::Tiled {
-                tile_x: false,
-                tile_y: true,
-                stretch_value: 1.0,
+fn complete_level(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::LevelComplete);
+}
+
+fn spawn_finish_line(
+    mut commands: Commands,
+    sheet: Res<SpriteSheet>,
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+) {
+    for (min, max) in current_level.0.markers.finish_zones() {
+        // The zone covers the tiles from min up to (but not including) max.
+        let corners = (min.to_vec2(), max.to_vec2());
+        let size = corners.1 - corners.0;
+        commands.spawn((
+            Name::new("Finish Line"),
+            FinishLine {
+                hitbox: Hitbox::from_corners(corners.0, corners.1),
             },
-            ..Default::default()
-        },
-        Transform {
-            translation: render_position_from_world_array_position(
-                (corners.0.x + corners.1.x) / 2.0 * TILE_DIM,
-                (corners.0.y + corners.1.y) / 2.0 * TILE_DIM,
-            )
-            .extend(Z.interactive),
-            scale: Vec3::splat(crate::gameplay::SCALE_FACTOR),
-            ..Default::default()
-        },
-        DespawnOnExit(Screen::Gameplay),
-    ));
+            MinimapIcon(FINISH_MINIMAP_COLOR),
+            Sprite {
+                image: sheet.texture.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: sheet.layout.clone(),
+                    index: 5,
+                }),
+                custom_size: Some(size * TILE_DIM / crate::gameplay::SCALE_FACTOR),
+                image_mode: SpriteImageMode::Tiled {
+                    tile_x: true,
+                    tile_y: true,
+                    stretch_value: 1.0,
+                },
+                ..Default::default()
+            },
+            Transform {
+                translation: render_position_from_world_array_position(
+                    (corners.0.x + corners.1.x) / 2.0 * TILE_DIM,
+                    (corners.0.y + corners.1.y) / 2.0 * TILE_DIM,
+                    world_map.dimensions(),
+                )
+                .extend(Z.interactive),
+                scale: Vec3::splat(crate::gameplay::SCALE_FACTOR),
+                ..Default::default()
+            },
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
 }
 
 fn detect_finish_line_crossing(
     finish_line_query: Query<&FinishLine>,
     player_query: Query<(&MovementController, &Transform), With<Player>>,
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+    run_stats: Res<RunStats>,
     mut next_maze_progress: ResMut<NextState<MazeProgress>>,
 ) {
-    if let (Ok(finish_line), Ok((controller, transform))) =
-        (finish_line_query.single(), player_query.single())
-    {
+    // The finish line doesn't count until everything has been picked up.
+    if current_level.0.require_all_collectibles && !run_stats.collected_all() {
+        return;
+    }
+    if let Ok((controller, transform)) = player_query.single() {
         // TODO: Shared code with movement?
         let player_position = world_array_position_from_render_position(
             transform.translation.x,
             transform.translation.y,
+            world_map.dimensions(),
         );
         // TODO: If a movement controller only sometimes has a girth, shouldn't it just be a different component?
         if let Some(player_girth) = controller.girth {
@@ -111,7 +206,10 @@ fn detect_finish_line_crossing(
                 player_position - half_girth,
                 player_position + half_girth,
             );
-            if finish_line.hitbox.contains_hitbox(player_hitbox) {
+            if finish_line_query
+                .iter()
+                .any(|finish_line| finish_line.hitbox.contains_hitbox(player_hitbox))
+            {
                 // do stuff
                 debug!("YOU WIN!");
                 next_maze_progress.set(MazeProgress::Finish);
@@ -0,0 +1,537 @@
+//! Procedurally generated mazes.
+//!
+//! A maze is carved on a grid of cells and then drawn onto a [`WorldMap`] where
+//! every cell and every wall between two cells takes up one tile. A maze of
+//! `columns` x `rows` cells is therefore `2 * columns + 1` x `2 * rows + 1` tiles.
+//! The player starts in the entrance on the west side of the bottom row of cells
+//! and finishes in the exit on the east side of the top row.
+
+use rand::{Rng, SeedableRng, seq::IndexedRandom, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+
+use crate::gameplay::{
+    environment::{GroundType, ObstructionType, Tile, WorldMap, coordinate::Coordinate},
+    level::{Level, LevelMarker, LevelMarkers},
+};
+
+/// The ways we know how to carve a maze. They all produce perfect mazes (exactly
+/// one route between any two cells) but each has its own texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeAlgorithm {
+    /// Long, winding corridors with few dead ends.
+    RecursiveBacktracker,
+    /// Lots of short dead ends branching from a central area.
+    Prim,
+    /// Evenly spread, with many short dead ends.
+    Kruskal,
+    /// Built one row at a time, which keeps memory use to a single row.
+    Eller,
+    /// An unbiased sample of every possible maze.
+    Wilson,
+}
+
+impl MazeAlgorithm {
+    pub const ALL: [MazeAlgorithm; 5] = [
+        MazeAlgorithm::RecursiveBacktracker,
+        MazeAlgorithm::Prim,
+        MazeAlgorithm::Kruskal,
+        MazeAlgorithm::Eller,
+        MazeAlgorithm::Wilson,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MazeGenerator {
+    pub algorithm: MazeAlgorithm,
+    /// Width of the maze in cells.
+    pub columns: u32,
+    /// Height of the maze in cells.
+    pub rows: u32,
+    pub seed: u64,
+}
+
+impl MazeGenerator {
+    /// Fewest cells along either side of a maze made by [`MazeGenerator::from_seed`].
+    pub const MIN_CELLS: u32 = 5;
+    /// Most cells along either side of a maze made by [`MazeGenerator::from_seed`].
+    pub const MAX_CELLS: u32 = 10;
+
+    /// A maze with the algorithm and size chosen by the seed, so the seed alone
+    /// is enough to reproduce it.
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        Self {
+            algorithm: *MazeAlgorithm::ALL.choose(&mut rng).unwrap(),
+            columns: rng.random_range(Self::MIN_CELLS..=Self::MAX_CELLS),
+            rows: rng.random_range(Self::MIN_CELLS..=Self::MAX_CELLS),
+            seed,
+        }
+    }
+
+    pub fn generate(&self) -> Level {
+        assert!(
+            self.columns > 0 && self.rows > 0,
+            "A maze needs at least one cell!"
+        );
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut passages = Passages::new(self.columns, self.rows);
+        match self.algorithm {
+            MazeAlgorithm::RecursiveBacktracker => recursive_backtracker(&mut passages, &mut rng),
+            MazeAlgorithm::Prim => prim(&mut passages, &mut rng),
+            MazeAlgorithm::Kruskal => kruskal(&mut passages, &mut rng),
+            MazeAlgorithm::Eller => eller(&mut passages, &mut rng),
+            MazeAlgorithm::Wilson => wilson(&mut passages, &mut rng),
+        }
+        let world_map = passages.to_world_map();
+        let (width, height) = (world_map.dimensions().x, world_map.dimensions().y);
+        Level {
+            name: format!("Seed {}", self.seed),
+            music: None,
+            torch_radius: None,
+            require_all_collectibles: false,
+            lives: None,
+            world_map,
+            markers: LevelMarkers(vec![
+                LevelMarker::PlayerStart {
+                    x: 0.5,
+                    y: height as f32 - 1.5,
+                },
+                LevelMarker::FinishZone {
+                    x: width as i32 - 1,
+                    y: 1,
+                    width: 1,
+                    height: 1,
+                },
+            ]),
+        }
+    }
+}
+
+/// Which walls between neighbouring cells have been knocked down.
+struct Passages {
+    columns: u32,
+    rows: u32,
+    /// Open to the cell on the east, indexed by cell.
+    east: Vec<bool>,
+    /// Open to the cell on the south, indexed by cell.
+    south: Vec<bool>,
+}
+
+impl Passages {
+    fn new(columns: u32, rows: u32) -> Self {
+        let cells = (columns * rows) as usize;
+        Self {
+            columns,
+            rows,
+            east: vec![false; cells],
+            south: vec![false; cells],
+        }
+    }
+
+    fn cell_count(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+
+    fn index(&self, cell: Coordinate) -> usize {
+        cell.y as usize * self.columns as usize + cell.x as usize
+    }
+
+    fn cell(&self, index: usize) -> Coordinate {
+        Coordinate::new(
+            (index % self.columns as usize) as i32,
+            (index / self.columns as usize) as i32,
+        )
+    }
+
+    fn neighbours(&self, cell: Coordinate) -> Vec<Coordinate> {
+        [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .into_iter()
+            .map(|(dx, dy)| Coordinate::new(cell.x + dx, cell.y + dy))
+            .filter(|neighbour| {
+                neighbour.x >= 0
+                    && neighbour.y >= 0
+                    && (neighbour.x as u32) < self.columns
+                    && (neighbour.y as u32) < self.rows
+            })
+            .collect()
+    }
+
+    /// Knock down the wall between two neighbouring cells.
+    fn carve(&mut self, a: Coordinate, b: Coordinate) {
+        let (first, second) = if (a.y, a.x) < (b.y, b.x) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let index = self.index(first);
+        if second.x > first.x {
+            self.east[index] = true;
+        } else {
+            self.south[index] = true;
+        }
+    }
+
+    fn to_world_map(&self) -> WorldMap {
+        let width = 2 * self.columns + 1;
+        let height = 2 * self.rows + 1;
+        let mut walls = vec![true; (width * height) as usize];
+        let mut open = |x: u32, y: u32| walls[(y * width + x) as usize] = false;
+
+        for index in 0..self.cell_count() {
+            let cell = self.cell(index);
+            let (x, y) = (2 * cell.x as u32 + 1, 2 * cell.y as u32 + 1);
+            open(x, y);
+            if self.east[index] {
+                open(x + 1, y);
+            }
+            if self.south[index] {
+                open(x, y + 1);
+            }
+        }
+        open(0, height - 2);
+        open(width - 1, 1);
+
+        let is_wall = |x: i32, y: i32| {
+            x >= 0
+                && y >= 0
+                && (x as u32) < width
+                && (y as u32) < height
+                && walls[(y as u32 * width + x as u32) as usize]
+        };
+        let tiles = (0..height as i32)
+            .flat_map(|y| (0..width as i32).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let obstruction = if !is_wall(x, y) {
+                    ObstructionType::None
+                } else {
+                    wall_piece(
+                        is_wall(x - 1, y) || is_wall(x + 1, y),
+                        is_wall(x, y - 1) || is_wall(x, y + 1),
+                    )
+                };
+                Tile::new(GroundType::Grass, obstruction)
+            })
+            .collect();
+        WorldMap::new(width, height, tiles)
+    }
+}
+
+/// Pick the wall sprite that joins up with the walls around it.
+fn wall_piece(horizontal_neighbour: bool, vertical_neighbour: bool) -> ObstructionType {
+    match (horizontal_neighbour, vertical_neighbour) {
+        (true, false) => ObstructionType::WallH,
+        (false, true) => ObstructionType::WallV,
+        _ => ObstructionType::Tower,
+    }
+}
+
+fn recursive_backtracker(passages: &mut Passages, rng: &mut impl Rng) {
+    let mut visited = vec![false; passages.cell_count()];
+    let start = passages.cell(rng.random_range(0..passages.cell_count()));
+    visited[passages.index(start)] = true;
+    let mut stack = vec![start];
+
+    while let Some(&current) = stack.last() {
+        let unvisited: Vec<_> = passages
+            .neighbours(current)
+            .into_iter()
+            .filter(|neighbour| !visited[passages.index(*neighbour)])
+            .collect();
+        if let Some(&next) = unvisited.choose(rng) {
+            passages.carve(current, next);
+            visited[passages.index(next)] = true;
+            stack.push(next);
+        } else {
+            stack.pop();
+        }
+    }
+}
+
+fn prim(passages: &mut Passages, rng: &mut impl Rng) {
+    let mut in_maze = vec![false; passages.cell_count()];
+    let mut frontier = Vec::new();
+
+    let start = passages.cell(rng.random_range(0..passages.cell_count()));
+    add_to_maze(start, passages, &mut in_maze, &mut frontier);
+
+    while !frontier.is_empty() {
+        let cell = frontier.swap_remove(rng.random_range(0..frontier.len()));
+        let connections: Vec<_> = passages
+            .neighbours(cell)
+            .into_iter()
+            .filter(|neighbour| in_maze[passages.index(*neighbour)])
+            .collect();
+        let &connection = connections.choose(rng).unwrap();
+        passages.carve(cell, connection);
+        add_to_maze(cell, passages, &mut in_maze, &mut frontier);
+    }
+}
+
+/// Mark a cell as part of the maze and add its neighbours that aren't yet to the frontier.
+fn add_to_maze(
+    cell: Coordinate,
+    passages: &Passages,
+    in_maze: &mut [bool],
+    frontier: &mut Vec<Coordinate>,
+) {
+    in_maze[passages.index(cell)] = true;
+    for neighbour in passages.neighbours(cell) {
+        if !in_maze[passages.index(neighbour)] && !frontier.contains(&neighbour) {
+            frontier.push(neighbour);
+        }
+    }
+}
+
+fn kruskal(passages: &mut Passages, rng: &mut impl Rng) {
+    let mut edges = Vec::new();
+    for index in 0..passages.cell_count() {
+        let cell = passages.cell(index);
+        if (cell.x as u32) + 1 < passages.columns {
+            edges.push((cell, Coordinate::new(cell.x + 1, cell.y)));
+        }
+        if (cell.y as u32) + 1 < passages.rows {
+            edges.push((cell, Coordinate::new(cell.x, cell.y + 1)));
+        }
+    }
+    edges.shuffle(rng);
+
+    let mut sets = DisjointSets::new(passages.cell_count());
+    for (a, b) in edges {
+        if sets.union(passages.index(a), passages.index(b)) {
+            passages.carve(a, b);
+        }
+    }
+}
+
+fn eller(passages: &mut Passages, rng: &mut impl Rng) {
+    let columns = passages.columns as usize;
+    let mut next_set = 0;
+    let mut row_sets: Vec<Option<usize>> = vec![None; columns];
+
+    for y in 0..passages.rows as i32 {
+        let last_row = y as u32 + 1 == passages.rows;
+        for set in row_sets.iter_mut().filter(|set| set.is_none()) {
+            *set = Some(next_set);
+            next_set += 1;
+        }
+
+        // Join neighbours in different sets. The last row must join them all.
+        for x in 0..columns - 1 {
+            let (left, right) = (row_sets[x].unwrap(), row_sets[x + 1].unwrap());
+            if left != right && (last_row || rng.random_bool(0.5)) {
+                passages.carve(
+                    Coordinate::new(x as i32, y),
+                    Coordinate::new(x as i32 + 1, y),
+                );
+                for set in row_sets.iter_mut().filter(|set| **set == Some(right)) {
+                    *set = Some(left);
+                }
+            }
+        }
+        if last_row {
+            break;
+        }
+
+        // Every set needs at least one way down, otherwise it would be cut off.
+        let mut below: Vec<Option<usize>> = vec![None; columns];
+        let mut sets: Vec<usize> = row_sets.iter().flatten().copied().collect();
+        sets.sort_unstable();
+        sets.dedup();
+        for set in sets {
+            let mut members: Vec<usize> =
+                (0..columns).filter(|&x| row_sets[x] == Some(set)).collect();
+            members.shuffle(rng);
+            let drops = rng.random_range(1..=members.len());
+            for &x in &members[..drops] {
+                passages.carve(
+                    Coordinate::new(x as i32, y),
+                    Coordinate::new(x as i32, y + 1),
+                );
+                below[x] = Some(set);
+            }
+        }
+        row_sets = below;
+    }
+}
+
+fn wilson(passages: &mut Passages, rng: &mut impl Rng) {
+    let mut in_maze = vec![false; passages.cell_count()];
+    in_maze[rng.random_range(0..passages.cell_count())] = true;
+    // Where the walk last left each cell. Revisiting a cell overwrites this, which
+    // erases any loop the walk made.
+    let mut exits: Vec<Option<Coordinate>> = vec![None; passages.cell_count()];
+
+    let mut remaining: Vec<usize> = (0..passages.cell_count())
+        .filter(|&index| !in_maze[index])
+        .collect();
+    remaining.shuffle(rng);
+
+    for start in remaining {
+        if in_maze[start] {
+            continue;
+        }
+        let mut cell = passages.cell(start);
+        while !in_maze[passages.index(cell)] {
+            let &next = passages.neighbours(cell).choose(rng).unwrap();
+            exits[passages.index(cell)] = Some(next);
+            cell = next;
+        }
+
+        let mut cell = passages.cell(start);
+        while !in_maze[passages.index(cell)] {
+            let index = passages.index(cell);
+            let next = exits[index].unwrap();
+            in_maze[index] = true;
+            passages.carve(cell, next);
+            cell = next;
+        }
+    }
+}
+
+/// Union-find over cell indices for Kruskal's algorithm.
+struct DisjointSets {
+    parents: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(count: usize) -> Self {
+        Self {
+            parents: (0..count).collect(),
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let parent = self.parents[index];
+        if parent == index {
+            return index;
+        }
+        let root = self.find(parent);
+        self.parents[index] = root;
+        root
+    }
+
+    /// Merge the sets holding `a` and `b`. Returns false if they were already joined.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        self.parents[b] = a;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{
+        level::validate::{LevelIssue, validate_level},
+        player::PLAYER_GIRTH,
+    };
+
+    const SEEDS: [u64; 4] = [0, 1, 42, u64::MAX];
+
+    fn generators() -> impl Iterator<Item = MazeGenerator> {
+        MazeAlgorithm::ALL.into_iter().flat_map(|algorithm| {
+            SEEDS.into_iter().map(move |seed| MazeGenerator {
+                algorithm,
+                columns: 7,
+                rows: 4,
+                seed,
+            })
+        })
+    }
+
+    #[test]
+    fn the_same_seed_makes_the_same_maze() {
+        for generator in generators() {
+            assert_eq!(
+                generator.generate().world_map,
+                generator.generate().world_map,
+                "{generator:?}"
+            );
+        }
+        for seed in SEEDS {
+            assert_eq!(
+                MazeGenerator::from_seed(seed),
+                MazeGenerator::from_seed(seed)
+            );
+        }
+    }
+
+    #[test]
+    fn every_cell_can_be_reached() {
+        for generator in generators() {
+            let level = generator.generate();
+            let mut markers = level.markers.0;
+            // Every open tile is checked anyway, but a required collectible in
+            // each cell makes the failure point at the cell that was cut off.
+            for y in 0..generator.rows as i32 {
+                for x in 0..generator.columns as i32 {
+                    markers.push(LevelMarker::Coin {
+                        x: 2 * x + 1,
+                        y: 2 * y + 1,
+                    });
+                }
+            }
+            let issues =
+                validate_level(&level.world_map, &LevelMarkers(markers), true, PLAYER_GIRTH);
+            assert_eq!(issues, Vec::<LevelIssue>::new(), "{generator:?}");
+        }
+    }
+
+    #[test]
+    fn seeded_mazes_stay_within_bounds() {
+        for seed in 0..100 {
+            let generator = MazeGenerator::from_seed(seed);
+            let cells = MazeGenerator::MIN_CELLS..=MazeGenerator::MAX_CELLS;
+            assert!(cells.contains(&generator.columns), "{generator:?}");
+            assert!(cells.contains(&generator.rows), "{generator:?}");
+        }
+    }
+
+    #[test]
+    fn wall_pieces_join_up_with_their_neighbours() {
+        assert_eq!(wall_piece(true, false), ObstructionType::WallH);
+        assert_eq!(wall_piece(false, true), ObstructionType::WallV);
+        assert_eq!(wall_piece(true, true), ObstructionType::Tower);
+        assert_eq!(wall_piece(false, false), ObstructionType::Tower);
+    }
+
+    #[test]
+    fn the_border_uses_the_right_pieces() {
+        for generator in generators() {
+            let world_map = generator.generate().world_map;
+            let piece = |x: u32, y: u32| {
+                world_map
+                    .at(Coordinate::new(x as i32, y as i32))
+                    .unwrap()
+                    .obstruction()
+            };
+            let (width, height) = (world_map.dimensions().x, world_map.dimensions().y);
+            // Beside a cell, the border runs straight along the side of the maze.
+            for x in (1..width).step_by(2) {
+                assert_eq!(piece(x, 0), ObstructionType::WallH, "{generator:?}");
+                assert_eq!(
+                    piece(x, height - 1),
+                    ObstructionType::WallH,
+                    "{generator:?}"
+                );
+            }
+            for y in (3..height - 1).step_by(2) {
+                assert_eq!(piece(0, y - 2), ObstructionType::WallV, "{generator:?}");
+                assert_eq!(piece(width - 1, y), ObstructionType::WallV, "{generator:?}");
+            }
+            // The corners away from the entrance and exit join two walls.
+            assert_eq!(piece(0, 0), ObstructionType::Tower, "{generator:?}");
+            assert_eq!(
+                piece(width - 1, height - 1),
+                ObstructionType::Tower,
+                "{generator:?}"
+            );
+            // The entrance and exit are open.
+            assert_eq!(piece(0, height - 2), ObstructionType::None, "{generator:?}");
+            assert_eq!(piece(width - 1, 1), ObstructionType::None, "{generator:?}");
+        }
+    }
+}
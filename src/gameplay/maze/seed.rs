@@ -0,0 +1,117 @@
+//! The seed behind a generated maze. Everything random about a generated maze
+//! is derived from it, so sharing a seed reproduces the maze exactly, which is
+//! what makes bug reports and speedrun comparisons possible.
+
+use bevy::prelude::*;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[reflect(Resource)]
+pub struct MazeSeed {
+    pub value: u64,
+    /// Set for the maze of the day, as days since the Unix epoch (UTC).
+    pub day: Option<u64>,
+}
+
+impl MazeSeed {
+    pub fn new(value: u64) -> Self {
+        Self { value, day: None }
+    }
+
+    /// A fresh seed that nobody has played before (probably).
+    pub fn random() -> Self {
+        Self::new(rand::random())
+    }
+
+    /// The same seed for everyone on the same (UTC) day.
+    pub fn daily() -> Self {
+        let day = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_secs() / SECONDS_PER_DAY)
+            .unwrap_or_default();
+        Self::for_day(day)
+    }
+
+    /// The maze of the day for `day`, in days since the Unix epoch (UTC).
+    pub fn for_day(day: u64) -> Self {
+        Self {
+            value: split_mix(day),
+            day: Some(day),
+        }
+    }
+
+    /// A short description for the HUD.
+    pub fn label(&self) -> String {
+        match self.day {
+            Some(day) => format!("Maze of the day {} (seed {})", date(day), self.value),
+            None => format!("Seed {}", self.value),
+        }
+    }
+}
+
+/// Spread consecutive days across the whole range of seeds.
+/// See <https://prng.di.unimi.it/splitmix64.c>.
+fn split_mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Format days since the Unix epoch as a `YYYY-MM-DD` date.
+/// See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn date(days: u64) -> String {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_maze_of_the_day_stays_the_same() {
+        // 2024-10-04. Changing this seed changes every past maze of the day.
+        let seed = MazeSeed::for_day(20_000);
+        assert_eq!(seed.value, 16_693_281_570_183_516_702);
+        assert_eq!(seed, MazeSeed::for_day(20_000));
+        assert_ne!(seed.value, MazeSeed::for_day(20_001).value);
+    }
+
+    #[test]
+    fn today_is_a_maze_of_the_day() {
+        let seed = MazeSeed::daily();
+        assert_eq!(seed, MazeSeed::for_day(seed.day.unwrap()));
+    }
+
+    #[test]
+    fn labels_show_the_seed_and_date() {
+        assert_eq!(MazeSeed::new(42).label(), "Seed 42");
+        assert_eq!(
+            MazeSeed::for_day(20_000).label(),
+            "Maze of the day 2024-10-04 (seed 16693281570183516702)"
+        );
+    }
+
+    #[test]
+    fn dates_count_from_the_epoch() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(19_782), "2024-02-29");
+        assert_eq!(date(20_000), "2024-10-04");
+    }
+}
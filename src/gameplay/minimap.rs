@@ -0,0 +1,193 @@
+//! A small map of the explored part of the maze, in the corner of the screen.
+//! It has one pixel per tile, scaled up to fit the panel, so it works for
+//! mazes of any size. The player is always marked; anything with a
+//! [`MinimapIcon`] is marked once its tile has been explored.
+//!
+//! The minimap can be toggled, and whether it's shown is kept in [`Settings`].
+
+use bevy::{
+    asset::RenderAssetUsages,
+    ecs::schedule::common_conditions::any_match_filter,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::{
+    AppSystems,
+    gameplay::{
+        environment::{WorldMap, coordinate::Coordinate},
+        fog::FogOfWar,
+        level::load_level,
+        player::Player,
+        utils::world_array_position_from_render_position,
+    },
+    input::{InputAction, action_just_pressed},
+    screens::Screen,
+    settings::Settings,
+    theme::palette::TEXT_BOX_BACKGROUND,
+};
+
+/// The longest side of the minimap, in pixels.
+const MINIMAP_SIZE: f32 = 180.0;
+/// #557847
+const OPEN_COLOR: Color = Color::srgb(0.333, 0.471, 0.278);
+/// #ddd369
+const OBSTRUCTION_COLOR: Color = Color::srgb(0.867, 0.827, 0.412);
+/// #fcfbcc
+const PLAYER_COLOR: Color = Color::srgb(0.988, 0.984, 0.800);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_minimap.after(load_level));
+    app.add_systems(
+        Update,
+        (
+            toggle_minimap
+                .run_if(action_just_pressed(InputAction::ToggleMinimap))
+                .in_set(AppSystems::RecordInput),
+            show_minimap
+                .run_if(resource_changed::<Settings>)
+                .in_set(AppSystems::Update),
+            draw_minimap
+                .run_if(
+                    resource_changed::<FogOfWar>
+                        .or(any_match_filter::<(With<MinimapIcon>, Changed<Transform>)>)
+                        .or(any_match_filter::<Changed<MinimapIcon>>)
+                        .or(any_component_removed::<MinimapIcon>),
+                )
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Marks a point of interest on the minimap, in the given color.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub(crate) struct MinimapIcon(pub Color);
+
+/// Marks the image node the minimap is drawn into.
+#[derive(Component, Debug)]
+struct Minimap;
+
+fn spawn_minimap(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    settings: Res<Settings>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let map_dim = world_map.dimensions();
+    let size = minimap_size(map_dim);
+    commands.spawn((
+        Name::new("Minimap"),
+        Minimap,
+        ImageNode::new(images.add(minimap_image(map_dim))),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(10),
+            right: px(10),
+            width: px(size.x),
+            height: px(size.y),
+            ..default()
+        },
+        BackgroundColor(TEXT_BOX_BACKGROUND),
+        minimap_visibility(&settings),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+/// The size of the minimap for a map, in pixels. Small maps get a whole
+/// number of pixels per tile so they stay crisp.
+fn minimap_size(map_dim: UVec2) -> Vec2 {
+    let scale = MINIMAP_SIZE / map_dim.max_element().max(1) as f32;
+    let scale = if scale >= 1.0 { scale.floor() } else { scale };
+    map_dim.as_vec2() * scale
+}
+
+fn minimap_image(map_dim: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: map_dim.x,
+            height: map_dim.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &Color::NONE.to_srgba().to_u8_array(),
+        TextureFormat::Rgba8UnormSrgb,
+        // Kept in the main world too, so it can be redrawn as the maze is explored.
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+fn minimap_visibility(settings: &Settings) -> Visibility {
+    if settings.show_minimap {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
+
+fn toggle_minimap(mut settings: ResMut<Settings>) {
+    settings.show_minimap = !settings.show_minimap;
+}
+
+fn show_minimap(settings: Res<Settings>, mut minimap: Single<&mut Visibility, With<Minimap>>) {
+    **minimap = minimap_visibility(&settings);
+}
+
+fn draw_minimap(
+    world_map: Res<WorldMap>,
+    fog: Res<FogOfWar>,
+    icons: Query<(&Transform, &MinimapIcon)>,
+    player: Single<&Transform, With<Player>>,
+    minimap: Single<(&mut ImageNode, &mut Node), With<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let map_dim = world_map.dimensions();
+    let (mut image_node, mut node) = minimap.into_inner();
+    // The map changes size when a level is hot reloaded.
+    if images
+        .get(&image_node.image)
+        .is_some_and(|image| image.size() != map_dim)
+    {
+        let size = minimap_size(map_dim);
+        image_node.image = images.add(minimap_image(map_dim));
+        node.width = px(size.x);
+        node.height = px(size.y);
+    }
+    let Some(image) = images.get_mut(&image_node.image) else {
+        return;
+    };
+
+    for (coordinate, tile) in world_map.iter() {
+        let color = if !fog.is_explored(coordinate) {
+            Color::NONE
+        } else if tile.is_obstruction() {
+            OBSTRUCTION_COLOR
+        } else {
+            OPEN_COLOR
+        };
+        let _ = image.set_color_at(coordinate.x as u32, coordinate.y as u32, color);
+    }
+
+    let tile_at = |transform: &Transform| {
+        Coordinate::from(world_array_position_from_render_position(
+            transform.translation.x,
+            transform.translation.y,
+            map_dim,
+        ))
+    };
+    let icons = icons
+        .iter()
+        .map(|(transform, icon)| (tile_at(transform), icon.0))
+        .filter(|(coordinate, _)| fog.is_explored(*coordinate));
+    // The player goes last, so it's drawn over anything it's standing on.
+    for (coordinate, color) in icons.chain([(tile_at(&player), PLAYER_COLOR)]) {
+        if world_map.contains(coordinate) {
+            let _ = image.set_color_at(coordinate.x as u32, coordinate.y as u32, color);
+        }
+    }
+}
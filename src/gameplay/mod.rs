@@ -13,10 +13,24 @@ use crate::{
 use bevy::prelude::*;
 
 mod animation;
+mod audio;
+mod camera;
+mod collectible;
+mod dialogue;
+mod enemy;
 mod environment;
-mod maze;
+mod fog;
+mod hint;
+mod hud;
+mod interactive;
+pub(crate) mod level;
+pub(crate) mod maze;
+mod minimap;
 mod movement;
+pub(crate) mod pathfinding;
 mod player;
+pub(crate) mod stats;
+mod touch;
 mod utils;
 
 const TILE_DIM: f32 = 1.0;
@@ -35,9 +49,25 @@ pub(super) fn plugin(app: &mut App) {
     app.load_resource::<SpriteSheet>();
     app.add_plugins((
         animation::plugin,
+        audio::plugin,
+        camera::plugin,
+        collectible::plugin,
+        dialogue::plugin,
+        enemy::plugin,
+        fog::plugin,
+        hint::plugin,
+        hud::plugin,
+        interactive::plugin,
+    ));
+    app.add_plugins((
+        level::plugin,
         player::plugin,
         maze::plugin,
+        minimap::plugin,
         movement::plugin,
+        pathfinding::plugin,
+        stats::plugin,
+        touch::plugin,
     ));
 }
 
@@ -58,6 +88,23 @@ impl FromWorld for SpriteSheet {
     }
 }
 
+/// Marks the mesh that draws the static world.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub(crate) struct Environment;
+
+fn environment_mesh(world_map: &WorldMap, meshes: &mut Assets<Mesh>) -> Handle<Mesh> {
+    build_tile_mesh(
+        crate::gameplay::environment::local_environment_objects(world_map),
+        &AtlasConfig {
+            cols: ATLAS_COLS,
+            rows: ATLAS_ROWS,
+        },
+        TILE_DIM,
+        meshes,
+    )
+}
+
 // A system that spawns the static world elements around the player
 pub(crate) fn spawn_environment(
     mut commands: Commands,
@@ -66,20 +113,13 @@ pub(crate) fn spawn_environment(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    let tile_mesh = build_tile_mesh(
-        crate::gameplay::environment::local_environment_objects(&world_map),
-        &AtlasConfig {
-            cols: ATLAS_COLS,
-            rows: ATLAS_ROWS,
-        },
-        TILE_DIM,
-        &mut meshes,
-    );
+    let tile_mesh = environment_mesh(&world_map, &mut meshes);
 
     let material = materials.add(ColorMaterial::from(sheet.texture.clone()));
 
     commands.spawn((
         Name::new("Environment"),
+        Environment,
         Mesh2d(tile_mesh),
         MeshMaterial2d(material),
         Transform::from_xyz(0.0, -TILE_DIM, Z.ground), // Account for Bevy using Y-Up coordinates and us using Y-Down
@@ -1,8 +1,16 @@
+//! Move characters through the maze. A character controller is the collection
+//! of systems that govern the movement of characters:
+//! - Something sets [`MovementController`] intent: player input in the
+//!   `player` module, for example.
+//! - [`apply_movement`] moves each controller by its intent and maximum speed,
+//!   stopping short of obstructions in the [`WorldMap`].
+
 use bevy::{platform::collections::HashMap, prelude::*};
 
 use crate::{
     AppSystems, PausableSystems,
     gameplay::{
+        dialogue::dialogue_open,
         environment::{Direction, WorldMap, coordinate::Coordinate},
         utils::{
             hitbox::Hitbox, render_x_from_world_array_x, render_y_from_world_array_y,
@@ -12,12 +20,21 @@ use crate::{
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_message::<WallBump>();
     app.add_systems(
         Update,
         apply_movement
+            .run_if(not(dialogue_open))
             .in_set(AppSystems::Update)
             .in_set(PausableSystems),
     );
+    // Stand still while dialogue is open, rather than walking on the spot.
+    app.add_systems(
+        Update,
+        stop_movement
+            .run_if(dialogue_open)
+            .in_set(AppSystems::RecordInput),
+    );
 }
 
 /// These are the movement parameters for our character controller.
@@ -35,6 +52,16 @@ pub struct MovementController {
 
     /// How large is the entity's hitbox for collisions?
     pub girth: Option<Vec2>,
+
+    /// Whether an obstruction stopped the last movement short.
+    pub blocked: bool,
+}
+
+/// Sent when an entity walks into an obstruction. Pressing on against the
+/// same obstruction doesn't send it again.
+#[derive(Message, Debug)]
+pub struct WallBump {
+    pub entity: Entity,
 }
 
 impl Default for MovementController {
@@ -44,10 +71,17 @@ impl Default for MovementController {
             // three tiles per second is a nice default, but we can still vary this per character.
             max_speed: 3.0,
             girth: None,
+            blocked: false,
         }
     }
 }
 
+fn stop_movement(mut controllers: Query<&mut MovementController>) {
+    for mut controller in &mut controllers {
+        controller.intent = Vec2::ZERO;
+    }
+}
+
 fn hitbox_surroundings(hitbox: &Hitbox) -> (Coordinate, Coordinate) {
     let min_x = hitbox.x1().floor() as i32 - 1;
     let max_x = hitbox.x2().ceil() as i32;
@@ -56,9 +90,11 @@ fn hitbox_surroundings(hitbox: &Hitbox) -> (Coordinate, Coordinate) {
     (Coordinate::new(min_x, min_y), Coordinate::new(max_x, max_y))
 }
 
+/// The hitboxes of the obstructions around an entity. Doors and gates are read
+/// from the [`WorldMap`] too, so they only get in the way while they're shut.
 fn gather_map_obstructions(
     surroundings: &(Coordinate, Coordinate),
-    world_map: &Res<WorldMap>,
+    world_map: &WorldMap,
 ) -> HashMap<Coordinate, Option<Hitbox>> {
     let mut obstructions = HashMap::new();
     for x in surroundings.0.x..=surroundings.1.x {
@@ -79,14 +115,14 @@ fn gather_map_obstructions(
 }
 
 /// Returns the exact position in that direction that should be traveled to,
-/// to avoid clipping into environment objects. Position returned is in world
-/// array space.
+/// to avoid clipping into environment objects, and whether an obstruction cut
+/// the travel short. Position returned is in world array space.
 fn apply_movement_in_one_direction(
     obstructions: &HashMap<Coordinate, Option<Hitbox>>,
     directional_translation: (Direction, f32),
     hitbox: &Hitbox,  // world array space
     half_girth: Vec2, // world array space
-) -> f32 {
+) -> (f32, bool) {
     let mut travel_to: f32;
     let collision_area: Hitbox; // Newly occupied space
     let use_gt: bool; // When deciding the furthest we should travel should we eliminate travel further from 0?
@@ -130,6 +166,7 @@ fn apply_movement_in_one_direction(
             adjustment_fn = |obs_hitbox, half_girth: Vec2| obs_hitbox.x2() + half_girth.x;
         }
     }
+    let intended_travel_to = travel_to;
     let min = Coordinate::from_vec2_floor(collision_area.min());
     let max = Coordinate::from_vec2_floor(collision_area.max());
     for x in min.x..=max.x {
@@ -148,23 +185,27 @@ fn apply_movement_in_one_direction(
             }
         }
     }
-    travel_to
+    (travel_to, travel_to != intended_travel_to)
 }
 
 fn apply_movement(
     time: Res<Time>,
-    mut movement_query: Query<(&mut MovementController, &mut Transform)>,
+    mut movement_query: Query<(Entity, &mut MovementController, &mut Transform)>,
     world_map: Res<WorldMap>,
+    mut wall_bumps: MessageWriter<WallBump>,
 ) {
-    for (controller, mut transform) in &mut movement_query {
+    let map_dim = world_map.dimensions();
+    for (entity, mut controller, mut transform) in &mut movement_query {
         if controller.intent == Vec2::ZERO {
-            return;
+            controller.blocked = false;
+            continue;
         }
         let velocity = controller.max_speed * controller.intent;
         let translation = velocity.extend(0.0) * time.delta_secs(); // world array space
         let position = world_array_position_from_render_position(
             transform.translation.x,
             transform.translation.y,
+            map_dim,
         );
         // debug!("Position: {:?}", position);
         if let Some(girth) = controller.girth {
@@ -173,31 +214,35 @@ fn apply_movement(
                 Hitbox::from_rounded_corners(position - half_girth, position + half_girth);
             let surroundings = hitbox_surroundings(&hitbox);
             let mut obstructions = gather_map_obstructions(&surroundings, &world_map);
+            let mut blocked = false;
             // Try to move by x
             if translation.x > 0.0 {
                 // debug!("x > 0");
-                let x_translation = apply_movement_in_one_direction(
+                let (x_translation, x_blocked) = apply_movement_in_one_direction(
                     &obstructions,
                     (Direction::East, translation.x),
                     &hitbox,
                     half_girth,
                 );
-                transform.translation.x = render_x_from_world_array_x(x_translation);
+                transform.translation.x = render_x_from_world_array_x(x_translation, map_dim);
+                blocked |= x_blocked;
             } else if translation.x < 0.0 {
                 // debug!("x < 0");
-                let x_translation = apply_movement_in_one_direction(
+                let (x_translation, x_blocked) = apply_movement_in_one_direction(
                     &obstructions,
                     (Direction::West, translation.x),
                     &hitbox,
                     half_girth,
                 );
-                transform.translation.x = render_x_from_world_array_x(x_translation);
+                transform.translation.x = render_x_from_world_array_x(x_translation, map_dim);
+                blocked |= x_blocked;
             }
             // Reset hitbox in case movement didn't proceed fully in x direction
             // TODO: Optimization opportunity?
             let position = world_array_position_from_render_position(
                 transform.translation.x,
                 transform.translation.y,
+                map_dim,
             );
             hitbox = Hitbox::from_rounded_corners(position - half_girth, position + half_girth);
             let updated_surroundings = hitbox_surroundings(&hitbox);
@@ -207,23 +252,29 @@ fn apply_movement(
             // Try to move by y
             if translation.y > 0.0 {
                 // debug!("y > 0");
-                let y_translation = apply_movement_in_one_direction(
+                let (y_translation, y_blocked) = apply_movement_in_one_direction(
                     &obstructions,
                     (Direction::South, translation.y),
                     &hitbox,
                     half_girth,
                 );
-                transform.translation.y = render_y_from_world_array_y(y_translation);
+                transform.translation.y = render_y_from_world_array_y(y_translation, map_dim);
+                blocked |= y_blocked;
             } else if translation.y < 0.0 {
                 // debug!("y < 0");
-                let y_translation = apply_movement_in_one_direction(
+                let (y_translation, y_blocked) = apply_movement_in_one_direction(
                     &obstructions,
                     (Direction::North, translation.y),
                     &hitbox,
                     half_girth,
                 );
-                transform.translation.y = render_y_from_world_array_y(y_translation);
+                transform.translation.y = render_y_from_world_array_y(y_translation, map_dim);
+                blocked |= y_blocked;
+            }
+            if blocked && !controller.blocked {
+                wall_bumps.write(WallBump { entity });
             }
+            controller.blocked = blocked;
         } else {
             todo!("Non-Hitbox movement is not implemented.")
         }
@@ -0,0 +1,315 @@
+//! Find routes through the maze. Paths step between neighbouring tiles, never
+//! diagonally, and avoid every tile that is an obstruction.
+//!
+//! An agent's girth (as in [`MovementController::girth`]) decides how many
+//! tiles across it needs to fit through. A path for a wide agent is the path
+//! of the top left tile of its footprint.
+//!
+//! Searches are A* with a Manhattan distance heuristic. [`PathCache`] keeps the
+//! paths found on the current [`WorldMap`], and forgets them when it changes.
+//!
+//! [`MovementController::girth`]: crate::gameplay::movement::MovementController::girth
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::gameplay::{
+    TILE_DIM,
+    environment::{WorldMap, coordinate::Coordinate},
+    utils::hitbox::Hitbox,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PathCache>();
+    app.add_systems(
+        PreUpdate,
+        clear_path_cache.run_if(resource_changed::<WorldMap>),
+    );
+}
+
+/// How close to a tile's center counts as having reached it, in tiles.
+const ARRIVAL_DISTANCE: f32 = 0.02;
+
+/// How many paths [`PathCache`] keeps before starting over.
+const MAX_CACHED_PATHS: usize = 256;
+
+/// The tiles a path can step to from any tile.
+pub const STEPS: [Coordinate; 4] = [
+    Coordinate { x: 0, y: -1 },
+    Coordinate { x: 0, y: 1 },
+    Coordinate { x: -1, y: 0 },
+    Coordinate { x: 1, y: 0 },
+];
+
+/// How many tiles across an agent with `girth` takes up. Agents without a
+/// girth take up one tile.
+pub fn footprint(girth: Option<Vec2>) -> i32 {
+    girth.map_or(1, |girth| {
+        ((girth.max_element() / TILE_DIM).ceil() as i32).max(1)
+    })
+}
+
+/// Whether an agent `footprint` tiles across fits with its top left tile at
+/// `coordinate`.
+pub fn fits(world_map: &WorldMap, coordinate: Coordinate, footprint: i32) -> bool {
+    (0..footprint).all(|dy| {
+        (0..footprint).all(|dx| {
+            world_map
+                .at(Coordinate::new(coordinate.x + dx, coordinate.y + dy))
+                .is_some_and(|tile| !tile.is_obstruction())
+        })
+    })
+}
+
+/// The top left tiles of every footprint that lies entirely within `hitbox`.
+pub fn footprints_within(hitbox: &Hitbox, footprint: i32) -> impl Iterator<Item = Coordinate> {
+    let min = Coordinate::from_vec2_floor(hitbox.min().ceil());
+    let max = Coordinate::from_vec2_floor(hitbox.max().floor());
+    (min.y..=max.y - footprint)
+        .flat_map(move |y| (min.x..=max.x - footprint).map(move |x| Coordinate::new(x, y)))
+}
+
+fn neighbours(
+    world_map: &WorldMap,
+    coordinate: Coordinate,
+    footprint: i32,
+) -> impl Iterator<Item = Coordinate> {
+    STEPS
+        .into_iter()
+        .map(move |step| Coordinate::new(coordinate.x + step.x, coordinate.y + step.y))
+        .filter(move |next| fits(world_map, *next, footprint))
+}
+
+fn manhattan_distance(a: Coordinate, b: Coordinate) -> u32 {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+}
+
+/// The shortest path from `from` to `to`, including both ends, or `None` if
+/// `to` can't be reached.
+pub fn find_path(
+    world_map: &WorldMap,
+    from: Coordinate,
+    to: Coordinate,
+    girth: Option<Vec2>,
+) -> Option<Vec<Coordinate>> {
+    let footprint = footprint(girth);
+    if !fits(world_map, from, footprint) || !fits(world_map, to, footprint) {
+        return None;
+    }
+
+    // Each tile remembers the tile it was best reached from, and how far it is.
+    let mut came_from = HashMap::new();
+    came_from.insert(from, (from, 0));
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((manhattan_distance(from, to), from.x, from.y)));
+    while let Some(Reverse((_, x, y))) = open.pop() {
+        let current = Coordinate::new(x, y);
+        if current == to {
+            let mut path = vec![to];
+            let mut tile = to;
+            while tile != from {
+                tile = came_from[&tile].0;
+                path.push(tile);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        let distance = came_from[&current].1 + 1;
+        for next in neighbours(world_map, current, footprint) {
+            if came_from
+                .get(&next)
+                .is_none_or(|(_, known)| distance < *known)
+            {
+                came_from.insert(next, (current, distance));
+                let estimate = distance + manhattan_distance(next, to);
+                open.push(Reverse((estimate, next.x, next.y)));
+            }
+        }
+    }
+    None
+}
+
+/// How many steps it takes to reach every tile that can be reached from
+/// `from`. Handy for checking that a maze can be solved, or for hints.
+pub fn distances_from(
+    world_map: &WorldMap,
+    from: Coordinate,
+    girth: Option<Vec2>,
+) -> HashMap<Coordinate, u32> {
+    let footprint = footprint(girth);
+    let mut distances = HashMap::new();
+    if !fits(world_map, from, footprint) {
+        return distances;
+    }
+    distances.insert(from, 0);
+    let mut frontier = VecDeque::from([from]);
+    while let Some(current) = frontier.pop_front() {
+        let distance = distances[&current] + 1;
+        for next in neighbours(world_map, current, footprint) {
+            distances.entry(next).or_insert_with(|| {
+                frontier.push_back(next);
+                distance
+            });
+        }
+    }
+    distances
+}
+
+/// The intent that walks an agent at `position` along `path`, whose next tile
+/// is last. Tiles already reached are dropped from the path. `step` is how far
+/// the agent can move this frame, so it slows down rather than overshoot the
+/// center of a tile. Returns `None` once the path has been walked.
+pub fn steer_along(
+    path: &mut Vec<Coordinate>,
+    position: Vec2,
+    step: f32,
+    footprint: i32,
+) -> Option<Vec2> {
+    while let Some(&next) = path.last() {
+        let offset = next.to_vec2() + Vec2::splat(footprint as f32 * TILE_DIM / 2.0) - position;
+        if offset.length() > ARRIVAL_DISTANCE {
+            return Some((offset / step.max(f32::EPSILON)).clamp_length_max(1.0));
+        }
+        path.pop();
+    }
+    None
+}
+
+/// Paths found on the current [`WorldMap`], so that asking again is free.
+#[derive(Resource, Debug, Default)]
+pub struct PathCache {
+    paths: HashMap<(Coordinate, Coordinate, i32), Option<Vec<Coordinate>>>,
+}
+
+impl PathCache {
+    /// Like [`find_path`], but remembers the answer.
+    pub fn find_path(
+        &mut self,
+        world_map: &WorldMap,
+        from: Coordinate,
+        to: Coordinate,
+        girth: Option<Vec2>,
+    ) -> Option<&[Coordinate]> {
+        if self.paths.len() >= MAX_CACHED_PATHS {
+            self.paths.clear();
+        }
+        self.paths
+            .entry((from, to, footprint(girth)))
+            .or_insert_with(|| find_path(world_map, from, to, girth))
+            .as_deref()
+    }
+
+    pub fn clear(&mut self) {
+        self.paths.clear();
+    }
+}
+
+fn clear_path_cache(mut path_cache: ResMut<PathCache>) {
+    path_cache.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::environment::{GroundType, ObstructionType, Tile};
+
+    /// A map drawn with the same symbols as a level file's obstructions.
+    fn map(rows: &[&str]) -> WorldMap {
+        let tiles = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|symbol| {
+                Tile::new(
+                    GroundType::Grass,
+                    ObstructionType::from_symbol(symbol).unwrap(),
+                )
+            })
+            .collect();
+        WorldMap::new(rows[0].len() as u32, rows.len() as u32, tiles)
+    }
+
+    fn path(coordinates: &[(i32, i32)]) -> Vec<Coordinate> {
+        coordinates
+            .iter()
+            .map(|&(x, y)| Coordinate::new(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn a_straight_corridor_is_walked_straight() {
+        let world_map = map(&["+-----+", "|.....|", "+-----+"]);
+        assert_eq!(
+            find_path(
+                &world_map,
+                Coordinate::new(1, 1),
+                Coordinate::new(5, 1),
+                None
+            ),
+            Some(path(&[(1, 1), (2, 1), (3, 1), (4, 1), (5, 1)]))
+        );
+    }
+
+    #[test]
+    fn a_dead_end_leads_nowhere() {
+        let world_map = map(&["+-----+", "|..|..|", "+-----+"]);
+        assert_eq!(
+            find_path(
+                &world_map,
+                Coordinate::new(1, 1),
+                Coordinate::new(5, 1),
+                None
+            ),
+            None
+        );
+        let distances = distances_from(&world_map, Coordinate::new(1, 1), None);
+        assert_eq!(distances.len(), 2);
+        assert!(!distances.contains_key(&Coordinate::new(4, 1)));
+    }
+
+    #[test]
+    fn wide_agents_do_not_squeeze_through_narrow_gaps() {
+        let world_map = map(&[
+            "+-----+", //
+            "|..|..|", "|.....|", "|..|..|", "+-----+",
+        ]);
+        let (from, to) = (Coordinate::new(1, 1), Coordinate::new(4, 1));
+        let wide = Some(Vec2::splat(1.5 * TILE_DIM));
+        assert_eq!(footprint(wide), 2);
+        assert_eq!(find_path(&world_map, from, to, wide), None);
+        assert!(find_path(&world_map, from, to, None).is_some());
+    }
+
+    #[test]
+    fn cached_paths_are_forgotten_when_the_map_changes() {
+        let mut app = App::new();
+        app.insert_resource(map(&["+-----+", "|.....|", "+-----+"]));
+        app.add_plugins(plugin);
+        app.update();
+
+        let (from, to) = (Coordinate::new(1, 1), Coordinate::new(5, 1));
+        app.world_mut()
+            .resource_scope(|world, mut path_cache: Mut<PathCache>| {
+                let world_map = world.resource::<WorldMap>();
+                assert!(path_cache.find_path(world_map, from, to, None).is_some());
+            });
+        app.update();
+        assert_eq!(app.world().resource::<PathCache>().paths.len(), 1);
+
+        app.world_mut()
+            .resource_mut::<WorldMap>()
+            .at_mut(Coordinate::new(3, 1))
+            .unwrap()
+            .set_obstruction(ObstructionType::Tower);
+        app.update();
+        assert!(app.world().resource::<PathCache>().paths.is_empty());
+        app.world_mut()
+            .resource_scope(|world, mut path_cache: Mut<PathCache>| {
+                let world_map = world.resource::<WorldMap>();
+                assert!(path_cache.find_path(world_map, from, to, None).is_none());
+            });
+    }
+}
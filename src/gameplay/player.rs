@@ -3,24 +3,43 @@ use bevy::{prelude::*, sprite::Anchor};
 use crate::{
     AppSystems, PausableSystems,
     gameplay::{
-        SpriteSheet, TILE_DIM, animation::PlayerAnimation, environment::Direction,
-        movement::MovementController, utils::render_position_from_world_array_position,
+        SpriteSheet, TILE_DIM,
+        animation::PlayerAnimation,
+        dialogue::dialogue_open,
+        environment::{Direction, WorldMap},
+        level::{CurrentLevel, LevelMarkers, load_level},
+        movement::MovementController,
+        stats::RunStats,
+        touch::WalkPath,
+        utils::render_position_from_world_array_position,
     },
+    input::{Actions, InputAction},
     screens::Screen,
+    settings::Settings,
     utils::Z,
 };
 
+/// How much room the player takes up, in world array units.
+pub const PLAYER_GIRTH: Vec2 = Vec2::splat(0.8 * TILE_DIM);
+
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
         record_player_directional_input
+            .run_if(not(dialogue_open))
             .in_set(AppSystems::RecordInput)
             .in_set(PausableSystems),
     );
-    app.add_systems(OnEnter(Screen::Gameplay), spawn_player);
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_player.after(load_level));
 }
 
-fn spawn_player(mut commands: Commands, sheet: Res<SpriteSheet>) {
+fn spawn_player(
+    mut commands: Commands,
+    sheet: Res<SpriteSheet>,
+    world_map: Res<WorldMap>,
+    current_level: Res<CurrentLevel>,
+) {
+    let start = current_level.0.markers.player_start() * TILE_DIM;
     let player_animation = PlayerAnimation::new(Direction::East);
 
     commands.spawn((
@@ -36,13 +55,17 @@ fn spawn_player(mut commands: Commands, sheet: Res<SpriteSheet>) {
         player_animation,
         Anchor::CENTER,
         Transform {
-            translation: render_position_from_world_array_position(0.5 * TILE_DIM, 9.0 * TILE_DIM)
-                .extend(Z.player),
+            translation: render_position_from_world_array_position(
+                start.x,
+                start.y,
+                world_map.dimensions(),
+            )
+            .extend(Z.player),
             scale: Vec3::splat(crate::gameplay::SCALE_FACTOR),
             ..Default::default()
         },
         MovementController {
-            girth: Some(Vec2::ONE * 0.8 * TILE_DIM),
+            girth: Some(PLAYER_GIRTH),
             ..Default::default()
         },
         DespawnOnExit(Screen::Gameplay),
@@ -53,32 +76,72 @@ fn spawn_player(mut commands: Commands, sheet: Res<SpriteSheet>) {
 #[reflect(Component)]
 pub struct Player;
 
+/// Put the player back on the level's start, as if they had just arrived.
+pub(super) fn send_player_to_start(
+    commands: &mut Commands,
+    (player, transform): (Entity, &mut Transform),
+    level_markers: &LevelMarkers,
+    map_dim: UVec2,
+    run_stats: &mut RunStats,
+) {
+    let start = level_markers.player_start() * TILE_DIM;
+    transform.translation = render_position_from_world_array_position(start.x, start.y, map_dim)
+        .extend(transform.translation.z);
+    // Being put back isn't walking back.
+    run_stats.forget_player_position();
+    commands.entity(player).remove::<WalkPath>();
+}
+
 // NOTE: This creates intent in world array coordinate space
-fn record_player_directional_input(
-    input: Res<ButtonInput<KeyCode>>,
+pub(super) fn record_player_directional_input(
+    actions: Actions,
+    gamepads: Query<&Gamepad>,
+    settings: Res<Settings>,
     mut controller_query: Query<&mut MovementController, With<Player>>,
 ) {
-    // Collect directional input.
+    // Collect directional input from the bound keys and buttons.
     let mut intent = Vec2::ZERO;
-    if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
+    if actions.pressed(InputAction::MoveUp) {
         intent.y -= 1.0;
     }
-    if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
+    if actions.pressed(InputAction::MoveDown) {
         intent.y += 1.0;
     }
-    if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
+    if actions.pressed(InputAction::MoveLeft) {
         intent.x -= 1.0;
     }
-    if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
+    if actions.pressed(InputAction::MoveRight) {
         intent.x += 1.0;
     }
 
     // Normalize intent so that diagonal movement is the same speed as horizontal / vertical.
-    // This should be omitted if the input comes from an analog stick instead.
-    let intent = intent.normalize_or_zero();
+    let mut intent = intent.normalize_or_zero();
+
+    // Without digital input, fall back to the first analog stick that is pushed.
+    // It isn't normalized, so a light push walks slowly.
+    if intent == Vec2::ZERO {
+        intent = gamepads
+            .iter()
+            .map(|gamepad| stick_intent(gamepad.left_stick(), settings.stick_dead_zone))
+            .find(|intent| *intent != Vec2::ZERO)
+            .unwrap_or_default();
+    }
 
     // Apply movement intent to controllers.
     for mut controller in &mut controller_query {
         controller.intent = intent;
     }
 }
+
+/// Turn an analog stick position into intent. Positions inside the dead zone are
+/// ignored, and the rest of the range is stretched so that movement starts from
+/// zero at its edge.
+fn stick_intent(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let dead_zone = dead_zone.clamp(0.0, 0.9);
+    let length = stick.length();
+    if length <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let strength = ((length - dead_zone) / (1.0 - dead_zone)).min(1.0);
+    Vec2::new(stick.x, -stick.y) / length * strength
+}
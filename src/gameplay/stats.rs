@@ -0,0 +1,136 @@
+//! Statistics about the current run through a maze. Only time spent seeking
+//! the finish line counts, and nothing is counted while the game is paused.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::Stopwatch};
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::{TILE_DIM, maze::MazeProgress, movement::WallBump, player::Player},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunStats>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_run_stats);
+    app.add_systems(
+        Update,
+        (
+            tick_run_timer.in_set(AppSystems::TickTimers),
+            count_wall_bumps.in_set(AppSystems::Update),
+        )
+            .run_if(in_state(MazeProgress::Seeking))
+            .in_set(PausableSystems),
+    );
+    // After everything has had a chance to move the player.
+    app.add_systems(
+        PostUpdate,
+        track_distance.run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct RunStats {
+    /// Time spent seeking the finish line.
+    pub time: Stopwatch,
+    /// How far the player has walked, in tiles.
+    pub distance: f32,
+    /// How many times the player has walked into a wall.
+    pub wall_bumps: u32,
+    /// How many hints the player has asked for.
+    pub hints: u32,
+    /// Points for the collectibles picked up.
+    pub score: u32,
+    /// How many collectibles have been picked up.
+    pub collected: u32,
+    /// How many collectibles the maze has.
+    pub collectibles: u32,
+    /// How many times enemies have caught the player.
+    pub caught: u32,
+    /// Where the player was at the end of the last frame.
+    last_player_position: Option<Vec2>,
+}
+
+impl RunStats {
+    pub fn time_label(&self) -> String {
+        format!("Time: {}", format_time(self.time.elapsed()))
+    }
+
+    pub fn distance_label(&self) -> String {
+        format!("Distance: {:.1} tiles", self.distance)
+    }
+
+    pub fn wall_bumps_label(&self) -> String {
+        format!("Wall bumps: {}", self.wall_bumps)
+    }
+
+    pub fn hints_label(&self) -> String {
+        format!("Hints: {}", self.hints)
+    }
+
+    pub fn score_label(&self) -> String {
+        format!("Score: {}", self.score)
+    }
+
+    pub fn collected_label(&self) -> String {
+        format!("Collected: {}/{}", self.collected, self.collectibles)
+    }
+
+    pub fn caught_label(&self) -> String {
+        format!("Caught: {}", self.caught)
+    }
+
+    /// Whether every collectible in the maze has been picked up.
+    pub fn collected_all(&self) -> bool {
+        self.collected >= self.collectibles
+    }
+
+    /// Don't count the player's next move towards the distance walked, for
+    /// when they are put somewhere rather than walking there.
+    pub fn forget_player_position(&mut self) {
+        self.last_player_position = None;
+    }
+}
+
+pub(super) fn reset_run_stats(mut run_stats: ResMut<RunStats>) {
+    *run_stats = RunStats::default();
+}
+
+fn tick_run_timer(time: Res<Time>, mut run_stats: ResMut<RunStats>) {
+    run_stats.time.tick(time.delta());
+}
+
+fn track_distance(
+    player_transform: Single<&Transform, With<Player>>,
+    maze_progress: Res<State<MazeProgress>>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let position = player_transform.translation.truncate();
+    if *maze_progress.get() == MazeProgress::Seeking
+        && let Some(last_position) = run_stats.last_player_position
+    {
+        run_stats.distance += position.distance(last_position) / TILE_DIM;
+    }
+    run_stats.last_player_position = Some(position);
+}
+
+fn count_wall_bumps(
+    mut wall_bumps: MessageReader<WallBump>,
+    player: Single<Entity, With<Player>>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let bumps = wall_bumps
+        .read()
+        .filter(|bump| bump.entity == *player)
+        .count();
+    run_stats.wall_bumps += bumps as u32;
+}
+
+/// Format a run time as `M:SS.ss`.
+pub(crate) fn format_time(duration: Duration) -> String {
+    let seconds = duration.as_secs_f32();
+    let minutes = (seconds / 60.0).floor();
+    format!("{minutes}:{:05.2}", seconds - minutes * 60.0)
+}
@@ -0,0 +1,360 @@
+//! Controls for playing without a keyboard, such as on a phone: an on-screen
+//! D-pad, and tapping (or clicking) a tile to walk there.
+//!
+//! The D-pad only shows up once the screen has been touched. Both only steer
+//! the player while there is no other directional input, and any other input
+//! cancels a walk to a tapped tile.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::{
+        dialogue::dialogue_open,
+        environment::{WorldMap, coordinate::Coordinate},
+        movement::MovementController,
+        pathfinding::{PathCache, footprint, steer_along},
+        player::{Player, record_player_directional_input},
+        utils::world_array_position_from_render_position,
+    },
+    screens::Screen,
+    theme::{interaction::InteractionPalette, palette::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TouchDetected>();
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_dpad);
+    app.add_systems(
+        Update,
+        (detect_touch, show_dpad)
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        Update,
+        (walk_to_tapped_tile, steer_player)
+            .chain()
+            .after(record_player_directional_input)
+            .run_if(not(dialogue_open))
+            .in_set(AppSystems::RecordInput)
+            .in_set(PausableSystems),
+    );
+}
+
+/// Whether the screen has been touched this session.
+#[derive(Resource, Debug, Default)]
+struct TouchDetected(bool);
+
+fn detect_touch(touches: Res<Touches>, mut touch_detected: ResMut<TouchDetected>) {
+    if !touch_detected.0 && touches.any_just_pressed() {
+        touch_detected.0 = true;
+    }
+}
+
+/// The tiles left to walk through to reach a tapped tile, nearest last.
+#[derive(Component, Debug, Default)]
+pub(super) struct WalkPath(Vec<Coordinate>);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DPad;
+
+/// A D-pad button, and the intent it adds while held.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DPadButton(Vec2);
+
+fn spawn_dpad(mut commands: Commands, touch_detected: Res<TouchDetected>) {
+    commands.spawn((
+        Name::new("D-Pad"),
+        DPad,
+        Node {
+            position_type: PositionType::Absolute,
+            right: px(30),
+            bottom: px(30),
+            display: Display::Grid,
+            grid_template_columns: RepeatedGridTrack::px(3, 80.0),
+            grid_template_rows: RepeatedGridTrack::px(3, 80.0),
+            ..default()
+        },
+        if touch_detected.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        },
+        DespawnOnExit(Screen::Gameplay),
+        // World array Y points down.
+        children![
+            dpad_button("^", Vec2::NEG_Y, 2, 1),
+            dpad_button("<", Vec2::NEG_X, 1, 2),
+            dpad_button(">", Vec2::X, 3, 2),
+            dpad_button("v", Vec2::Y, 2, 3),
+        ],
+    ));
+}
+
+fn dpad_button(text: &'static str, direction: Vec2, column: i16, row: i16) -> impl Bundle {
+    (
+        Name::new("D-Pad Button"),
+        DPadButton(direction),
+        // No `Button`, so that menu navigation doesn't focus it.
+        Interaction::default(),
+        Node {
+            grid_column: GridPlacement::start(column),
+            grid_row: GridPlacement::start(row),
+            margin: UiRect::all(px(5)),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(BUTTON_BACKGROUND),
+        BorderRadius::all(px(10)),
+        InteractionPalette {
+            none: BUTTON_BACKGROUND,
+            hovered: BUTTON_HOVERED_BACKGROUND,
+            pressed: BUTTON_PRESSED_BACKGROUND,
+        },
+        children![(
+            Name::new("D-Pad Button Text"),
+            Text(text.to_string()),
+            TextFont::from_font_size(40.0),
+            TextColor(BUTTON_TEXT),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+fn show_dpad(touch_detected: Res<TouchDetected>, mut dpad: Single<&mut Visibility, With<DPad>>) {
+    if touch_detected.0 {
+        dpad.set_if_neq(Visibility::Inherited);
+    }
+}
+
+/// Plan a path to the tile under a tap or left click.
+fn walk_to_tapped_tile(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    interactions: Query<&Interaction>,
+    world_map: Res<WorldMap>,
+    mut path_cache: ResMut<PathCache>,
+    player: Single<(Entity, &Transform, &MovementController), With<Player>>,
+) {
+    let tap = touches
+        .iter_just_pressed()
+        .map(|touch| touch.position())
+        .next()
+        .or_else(|| {
+            mouse
+                .just_pressed(MouseButton::Left)
+                .then(|| window.cursor_position())
+                .flatten()
+        });
+    let Some(tap) = tap else {
+        return;
+    };
+    // Taps on the D-pad or other UI aren't meant for the maze.
+    if interactions
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+
+    let (camera, camera_transform) = *camera;
+    let Ok(tap) = camera.viewport_to_world_2d(camera_transform, tap) else {
+        return;
+    };
+    let map_dim = world_map.dimensions();
+    let target = Coordinate::from(world_array_position_from_render_position(
+        tap.x, tap.y, map_dim,
+    ));
+    let (entity, transform, controller) = *player;
+    let start = Coordinate::from(world_array_position_from_render_position(
+        transform.translation.x,
+        transform.translation.y,
+        map_dim,
+    ));
+
+    if let Some(path) = path_cache.find_path(&world_map, start, target, controller.girth) {
+        commands
+            .entity(entity)
+            .insert(WalkPath(path.iter().rev().copied().collect()));
+    }
+}
+
+/// Steer the player with the D-pad, or else along its path. Other directional
+/// input takes priority, and cancels the path.
+fn steer_player(
+    mut commands: Commands,
+    time: Res<Time>,
+    world_map: Res<WorldMap>,
+    dpad_buttons: Query<(&Interaction, &DPadButton)>,
+    player: Single<
+        (
+            Entity,
+            &Transform,
+            &mut MovementController,
+            Option<&mut WalkPath>,
+        ),
+        With<Player>,
+    >,
+) {
+    let (entity, transform, mut controller, path) = player.into_inner();
+    let dpad_intent = dpad_buttons
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| button.0)
+        .sum::<Vec2>()
+        .normalize_or_zero();
+
+    if controller.intent != Vec2::ZERO || dpad_intent != Vec2::ZERO {
+        if path.is_some() {
+            commands.entity(entity).remove::<WalkPath>();
+        }
+        if controller.intent == Vec2::ZERO {
+            controller.intent = dpad_intent;
+        }
+        return;
+    }
+    let Some(mut path) = path else {
+        return;
+    };
+
+    let position = world_array_position_from_render_position(
+        transform.translation.x,
+        transform.translation.y,
+        world_map.dimensions(),
+    );
+    let step = controller.max_speed * time.delta_secs();
+    match steer_along(&mut path.0, position, step, footprint(controller.girth)) {
+        Some(intent) => controller.intent = intent,
+        None => {
+            commands.entity(entity).remove::<WalkPath>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        camera::{ComputedCameraValues, NormalizedRenderTarget, RenderTargetInfo},
+        picking::{
+            backend::HitData,
+            events::{Click, Pointer},
+            pointer::{Location, PointerButton, PointerId},
+        },
+    };
+
+    use super::*;
+    use crate::gameplay::{
+        dialogue::{ActiveDialogue, DialogueLine, DialogueScript, start_dialogue},
+        environment::{GroundType, ObstructionType, Tile},
+    };
+
+    const WINDOW_SIZE: f32 = 200.0;
+
+    /// Just enough of the game to tap on an open 9x9 maze, with a camera one
+    /// pixel to a world unit centered on it.
+    fn app() -> App {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<MouseButton>>();
+        app.init_resource::<Touches>();
+        app.init_resource::<PathCache>();
+        app.insert_resource(WorldMap::new(
+            9,
+            9,
+            vec![Tile::new(GroundType::Grass, ObstructionType::None); 81],
+        ));
+        app.add_systems(Update, walk_to_tapped_tile.run_if(not(dialogue_open)));
+
+        let mut window = Window::default();
+        window.resolution.set(WINDOW_SIZE, WINDOW_SIZE);
+        window.set_cursor_position(Some(Vec2::splat(WINDOW_SIZE / 2.0)));
+        app.world_mut().spawn((window, PrimaryWindow));
+        let half = WINDOW_SIZE / 2.0;
+        app.world_mut().spawn((
+            Camera2d,
+            Camera {
+                computed: ComputedCameraValues {
+                    clip_from_view: Mat4::orthographic_rh(-half, half, -half, half, -1.0, 1.0),
+                    target_info: Some(RenderTargetInfo {
+                        physical_size: UVec2::splat(WINDOW_SIZE as u32),
+                        scale_factor: 1.0,
+                    }),
+                    ..default()
+                },
+                ..default()
+            },
+            GlobalTransform::default(),
+        ));
+        app.world_mut()
+            .spawn((Player, Transform::default(), MovementController::default()));
+        app
+    }
+
+    fn walking(app: &mut App) -> bool {
+        app.world_mut()
+            .query_filtered::<Has<WalkPath>, With<Player>>()
+            .single(app.world())
+            .unwrap()
+    }
+
+    #[test]
+    fn clicking_the_maze_walks_there() {
+        let mut app = app();
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Left);
+        app.update();
+        assert!(walking(&mut app));
+    }
+
+    #[test]
+    fn clicking_away_dialogue_does_not_walk() {
+        let mut app = app();
+        let script = DialogueScript {
+            lines: vec![DialogueLine {
+                speaker: None,
+                text: String::new(),
+            }],
+        };
+        start_dialogue(&mut app.world_mut().commands(), Some(&script));
+        app.world_mut().flush();
+        app.update();
+
+        // The pointer is pressed and released over the text box in one frame,
+        // which closes the dialogue before the maze sees the press.
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Left);
+        let text_box = app
+            .world_mut()
+            .query_filtered::<Entity, (With<Node>, Without<ChildOf>)>()
+            .iter(app.world())
+            .next()
+            .unwrap();
+        app.world_mut().trigger(Pointer::new(
+            PointerId::Mouse,
+            Location {
+                target: NormalizedRenderTarget::None {
+                    width: WINDOW_SIZE as u32,
+                    height: WINDOW_SIZE as u32,
+                },
+                position: Vec2::splat(WINDOW_SIZE / 2.0),
+            },
+            Click {
+                button: PointerButton::Primary,
+                hit: HitData::new(text_box, 0.0, None, None),
+                duration: default(),
+            },
+            text_box,
+        ));
+        app.update();
+
+        assert!(!app.world().contains_resource::<ActiveDialogue>());
+        assert!(!walking(&mut app));
+    }
+}
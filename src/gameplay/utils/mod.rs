@@ -1,8 +1,6 @@
 use std::ops::Neg;
 
-use bevy::math::Vec2;
-
-use crate::SCREEN_DIM;
+use bevy::math::{UVec2, Vec2};
 
 pub mod hitbox;
 
@@ -14,31 +12,39 @@ pub mod hitbox;
 
 // "World array position" here is my left handed, Y-Down position.
 // (thumb is x, index is y, middle is z)
-// The axis is centered in the top left of the screen.
+// The axis is centered in the top left of the map.
+
+// The map is drawn centered on the render origin, so converting between the two
+// spaces needs the map's dimensions (in tiles).
 
-pub fn world_array_position_from_render_position(x: f32, y: f32) -> Vec2 {
-    let offset = SCREEN_DIM as f32 / 2.0;
+pub fn world_array_position_from_render_position(x: f32, y: f32, map_dim: UVec2) -> Vec2 {
+    let offset = map_offset(map_dim);
     // render position is to the right (positive x) and to the bottom (negative y in render space)
-    Vec2::new(x + offset, flipped_y_axis(y) - flipped_y_axis(offset))
+    Vec2::new(x + offset.x, flipped_y_axis(y) - flipped_y_axis(offset.y))
 }
 
-pub fn render_position_from_world_array_position(x: f32, y: f32) -> Vec2 {
+pub fn render_position_from_world_array_position(x: f32, y: f32, map_dim: UVec2) -> Vec2 {
     Vec2::new(
-        render_x_from_world_array_x(x),
-        render_y_from_world_array_y(y),
+        render_x_from_world_array_x(x, map_dim),
+        render_y_from_world_array_y(y, map_dim),
     )
 }
 
-pub fn render_x_from_world_array_x(x: f32) -> f32 {
-    let offset = SCREEN_DIM as f32 / 2.0;
+pub fn render_x_from_world_array_x(x: f32, map_dim: UVec2) -> f32 {
+    let offset = map_offset(map_dim);
     // world array position is to the left (negative x)
-    x - offset
+    x - offset.x
 }
 
-pub fn render_y_from_world_array_y(y: f32) -> f32 {
-    let offset = SCREEN_DIM as f32 / 2.0;
+pub fn render_y_from_world_array_y(y: f32, map_dim: UVec2) -> f32 {
+    let offset = map_offset(map_dim);
     // world array position is to the top (negative y in world array space)
-    flipped_y_axis(y) - flipped_y_axis(offset)
+    flipped_y_axis(y) - flipped_y_axis(offset.y)
+}
+
+/// Distance from the top left of the map to its center, in world array space.
+fn map_offset(map_dim: UVec2) -> Vec2 {
+    map_dim.as_vec2() / 2.0
 }
 
 /// A documentation as code function making it explicit where we have to account
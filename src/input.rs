@@ -0,0 +1,399 @@
+//! Input actions. Systems ask whether an [`InputAction`] is pressed rather than
+//! reading keys or gamepad buttons directly, so the player can rebind them.
+//! The bindings are part of [`Settings`].
+//!
+//! While [`BindingCapture`] is waiting for a new binding, no action reads as
+//! pressed, and the next key or gamepad button pressed is bound instead.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<BindingCapture>();
+    // After `Update`, so that the press being captured isn't also read as an
+    // action this frame.
+    app.add_systems(PostUpdate, capture_binding);
+}
+
+/// Something the player can do, whatever it is bound to.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Interact,
+    Hint,
+    ToggleMinimap,
+    Pause,
+    Confirm,
+    Back,
+    ToggleDebug,
+}
+
+impl InputAction {
+    pub const ALL: [Self; 11] = [
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Interact,
+        Self::Hint,
+        Self::ToggleMinimap,
+        Self::Pause,
+        Self::Confirm,
+        Self::Back,
+        Self::ToggleDebug,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MoveUp => "Move Up",
+            Self::MoveDown => "Move Down",
+            Self::MoveLeft => "Move Left",
+            Self::MoveRight => "Move Right",
+            Self::Interact => "Interact",
+            Self::Hint => "Hint",
+            Self::ToggleMinimap => "Toggle Minimap",
+            Self::Pause => "Pause",
+            Self::Confirm => "Confirm",
+            Self::Back => "Back",
+            Self::ToggleDebug => "Toggle Debug",
+        }
+    }
+}
+
+/// A key or gamepad button that triggers an action.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Key(KeyCode),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    pub fn name(self) -> String {
+        match self {
+            Self::Key(key) => {
+                let name = format!("{key:?}");
+                ["Key", "Digit", "Arrow"]
+                    .iter()
+                    .find_map(|prefix| name.strip_prefix(prefix))
+                    .unwrap_or(&name)
+                    .to_string()
+            }
+            Self::Gamepad(button) => format!("Pad {button:?}"),
+        }
+    }
+}
+
+/// What each action is bound to. Any of the bindings for an action will do.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Bindings {
+    pub move_up: Vec<Binding>,
+    pub move_down: Vec<Binding>,
+    pub move_left: Vec<Binding>,
+    pub move_right: Vec<Binding>,
+    pub interact: Vec<Binding>,
+    pub hint: Vec<Binding>,
+    pub toggle_minimap: Vec<Binding>,
+    pub pause: Vec<Binding>,
+    pub confirm: Vec<Binding>,
+    pub back: Vec<Binding>,
+    pub toggle_debug: Vec<Binding>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use Binding::{Gamepad, Key};
+
+        Self {
+            move_up: vec![
+                Key(KeyCode::KeyW),
+                Key(KeyCode::ArrowUp),
+                Gamepad(GamepadButton::DPadUp),
+            ],
+            move_down: vec![
+                Key(KeyCode::KeyS),
+                Key(KeyCode::ArrowDown),
+                Gamepad(GamepadButton::DPadDown),
+            ],
+            move_left: vec![
+                Key(KeyCode::KeyA),
+                Key(KeyCode::ArrowLeft),
+                Gamepad(GamepadButton::DPadLeft),
+            ],
+            move_right: vec![
+                Key(KeyCode::KeyD),
+                Key(KeyCode::ArrowRight),
+                Gamepad(GamepadButton::DPadRight),
+            ],
+            interact: vec![Key(KeyCode::KeyE), Gamepad(GamepadButton::West)],
+            hint: vec![Key(KeyCode::KeyH), Gamepad(GamepadButton::North)],
+            toggle_minimap: vec![Key(KeyCode::KeyM), Gamepad(GamepadButton::Select)],
+            pause: vec![Key(KeyCode::KeyP), Gamepad(GamepadButton::Start)],
+            confirm: vec![
+                Key(KeyCode::Enter),
+                Key(KeyCode::Space),
+                Gamepad(GamepadButton::South),
+            ],
+            back: vec![Key(KeyCode::Escape), Gamepad(GamepadButton::East)],
+            toggle_debug: vec![Key(KeyCode::Backquote)],
+        }
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, action: InputAction) -> &[Binding] {
+        match action {
+            InputAction::MoveUp => &self.move_up,
+            InputAction::MoveDown => &self.move_down,
+            InputAction::MoveLeft => &self.move_left,
+            InputAction::MoveRight => &self.move_right,
+            InputAction::Interact => &self.interact,
+            InputAction::Hint => &self.hint,
+            InputAction::ToggleMinimap => &self.toggle_minimap,
+            InputAction::Pause => &self.pause,
+            InputAction::Confirm => &self.confirm,
+            InputAction::Back => &self.back,
+            InputAction::ToggleDebug => &self.toggle_debug,
+        }
+    }
+
+    pub fn get_mut(&mut self, action: InputAction) -> &mut Vec<Binding> {
+        match action {
+            InputAction::MoveUp => &mut self.move_up,
+            InputAction::MoveDown => &mut self.move_down,
+            InputAction::MoveLeft => &mut self.move_left,
+            InputAction::MoveRight => &mut self.move_right,
+            InputAction::Interact => &mut self.interact,
+            InputAction::Hint => &mut self.hint,
+            InputAction::ToggleMinimap => &mut self.toggle_minimap,
+            InputAction::Pause => &mut self.pause,
+            InputAction::Confirm => &mut self.confirm,
+            InputAction::Back => &mut self.back,
+            InputAction::ToggleDebug => &mut self.toggle_debug,
+        }
+    }
+
+    /// Bind `binding` to `action` in place of the binding at `index`, or as an
+    /// extra binding when there is no `index`. The action's other bindings
+    /// are left alone.
+    ///
+    /// No two actions share a binding: if another action already had
+    /// `binding`, the two swap, and it takes over the binding `action` gave up.
+    fn rebind(&mut self, action: InputAction, index: Option<usize>, binding: Binding) {
+        let index = index.filter(|index| *index < self.get(action).len());
+        let replaced = index
+            .map(|index| self.get(action)[index])
+            .filter(|replaced| *replaced != binding);
+        for other in InputAction::ALL {
+            if other == action {
+                continue;
+            }
+            let bindings = self.get_mut(other);
+            if let Some(position) = bindings.iter().position(|existing| *existing == binding) {
+                match replaced {
+                    Some(replaced) if !bindings.contains(&replaced) => {
+                        bindings[position] = replaced;
+                    }
+                    _ => {
+                        bindings.remove(position);
+                    }
+                }
+            }
+        }
+
+        let bindings = self.get_mut(action);
+        match (
+            index,
+            bindings.iter().position(|existing| *existing == binding),
+        ) {
+            // Already bound elsewhere in the list, so the slot just goes.
+            (Some(index), Some(position)) if position != index => {
+                bindings.remove(index);
+            }
+            (Some(index), _) => bindings[index] = binding,
+            (None, Some(_)) => {}
+            (None, None) => bindings.push(binding),
+        }
+    }
+}
+
+/// The state of every action, read through the current bindings.
+#[derive(SystemParam)]
+pub struct Actions<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+    settings: Res<'w, Settings>,
+    capture: Res<'w, BindingCapture>,
+}
+
+impl Actions<'_, '_> {
+    /// Whether any binding for `action` is held down.
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.any(action, |keys, key| keys.pressed(key), Gamepad::pressed)
+    }
+
+    /// Whether any binding for `action` was pressed this frame.
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.any(
+            action,
+            |keys, key| keys.just_pressed(key),
+            Gamepad::just_pressed,
+        )
+    }
+
+    fn any(
+        &self,
+        action: InputAction,
+        key_down: impl Fn(&ButtonInput<KeyCode>, KeyCode) -> bool,
+        button_down: impl Fn(&Gamepad, GamepadButton) -> bool,
+    ) -> bool {
+        if self.capture.0.is_some() {
+            return false;
+        }
+        self.settings
+            .bindings
+            .get(action)
+            .iter()
+            .any(|binding| match *binding {
+                Binding::Key(key) => key_down(&self.keys, key),
+                Binding::Gamepad(button) => self
+                    .gamepads
+                    .iter()
+                    .any(|gamepad| button_down(gamepad, button)),
+            })
+    }
+}
+
+/// A run condition that is true on the frame `action` is pressed.
+pub fn action_just_pressed(action: InputAction) -> impl FnMut(Actions) -> bool + Clone {
+    move |actions: Actions| actions.just_pressed(action)
+}
+
+/// The binding slot waiting for a new binding, if any. Escape cancels.
+#[derive(Resource, Debug, Default)]
+pub struct BindingCapture(pub Option<BindingSlot>);
+
+/// One of an action's bindings, or a new one when there is no `index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindingSlot {
+    pub action: InputAction,
+    pub index: Option<usize>,
+}
+
+fn capture_binding(
+    mut capture: ResMut<BindingCapture>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut settings: ResMut<Settings>,
+) {
+    // Skip the frame capture started on, so the click that started it isn't
+    // captured as well.
+    if capture.is_changed() {
+        return;
+    }
+    let Some(slot) = capture.0 else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Escape) {
+        capture.0 = None;
+        return;
+    }
+
+    let binding = keys
+        .get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key))
+        .or_else(|| {
+            gamepads
+                .iter()
+                .find_map(|gamepad| gamepad.get_just_pressed().next())
+                .map(|button| Binding::Gamepad(*button))
+        });
+    if let Some(binding) = binding {
+        settings.bindings.rebind(slot.action, slot.index, binding);
+        capture.0 = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_to_a_used_key_swaps_it() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(InputAction::Interact, Some(0), Binding::Key(KeyCode::Space));
+
+        assert_eq!(
+            bindings.get(InputAction::Interact),
+            [
+                Binding::Key(KeyCode::Space),
+                Binding::Gamepad(GamepadButton::West),
+            ]
+        );
+        assert_eq!(
+            bindings.get(InputAction::Confirm),
+            [
+                Binding::Key(KeyCode::Enter),
+                Binding::Key(KeyCode::KeyE),
+                Binding::Gamepad(GamepadButton::South),
+            ]
+        );
+    }
+
+    #[test]
+    fn rebinding_to_a_free_key_leaves_other_actions_alone() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(InputAction::Hint, Some(0), Binding::Key(KeyCode::KeyJ));
+
+        assert_eq!(
+            bindings.get(InputAction::Hint),
+            [
+                Binding::Key(KeyCode::KeyJ),
+                Binding::Gamepad(GamepadButton::North),
+            ]
+        );
+        let default = Bindings::default();
+        for action in InputAction::ALL {
+            if action != InputAction::Hint {
+                assert_eq!(bindings.get(action), default.get(action));
+            }
+        }
+    }
+
+    #[test]
+    fn rebinding_one_key_keeps_the_alternates() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(InputAction::MoveUp, Some(0), Binding::Key(KeyCode::KeyI));
+
+        assert_eq!(
+            bindings.get(InputAction::MoveUp),
+            [
+                Binding::Key(KeyCode::KeyI),
+                Binding::Key(KeyCode::ArrowUp),
+                Binding::Gamepad(GamepadButton::DPadUp),
+            ]
+        );
+    }
+
+    #[test]
+    fn binding_without_a_slot_adds_a_binding() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(InputAction::Hint, None, Binding::Key(KeyCode::KeyJ));
+        bindings.rebind(InputAction::Hint, None, Binding::Key(KeyCode::KeyJ));
+
+        assert_eq!(
+            bindings.get(InputAction::Hint),
+            [
+                Binding::Key(KeyCode::KeyH),
+                Binding::Gamepad(GamepadButton::North),
+                Binding::Key(KeyCode::KeyJ),
+            ]
+        );
+    }
+}
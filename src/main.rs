@@ -8,8 +8,12 @@ mod audio;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod gameplay;
+mod input;
 mod menus;
+mod save;
 mod screens;
+mod settings;
+mod storage;
 mod theme;
 mod utils;
 
@@ -21,6 +25,10 @@ const SCREEN_PADDING: f32 = 0.5;
 const PIXELS_PER_TILE: u8 = 16;
 
 fn main() -> AppExit {
+    #[cfg(feature = "dev")]
+    if let Some(exit) = dev_tools::lint_levels_from_args() {
+        return exit;
+    }
     App::new().add_plugins(AppPlugin).run()
 }
 
@@ -61,8 +69,11 @@ impl Plugin for AppPlugin {
             #[cfg(feature = "dev")]
             dev_tools::plugin,
             gameplay::plugin,
+            input::plugin,
             menus::plugin,
+            save::plugin,
             screens::plugin,
+            settings::plugin,
             theme::plugin,
         ));
 
@@ -0,0 +1,218 @@
+//! The controls menu, for rebinding [`InputAction`]s. It is opened from the
+//! settings menu and edits the bindings in [`Settings`].
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    input::{BindingCapture, BindingSlot, Bindings, InputAction, action_just_pressed},
+    menus::Menu,
+    settings::Settings,
+    theme::{interaction::InteractionPalette, palette::*, widget},
+};
+
+/// Shown in place of a binding while waiting for its replacement.
+const CAPTURE_PROMPT: &str = "Press a key or button...";
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Controls), spawn_controls_menu);
+    app.add_systems(OnExit(Menu::Controls), stop_capture);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Controls).and(action_just_pressed(InputAction::Back))),
+    );
+
+    app.add_systems(
+        Update,
+        (update_binding_lists, update_binding_labels)
+            .chain()
+            .run_if(in_state(Menu::Controls)),
+    );
+}
+
+fn spawn_controls_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Controls Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Controls),
+        children![
+            widget::header("Controls"),
+            (
+                Name::new("Bindings"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: px(10),
+                    ..default()
+                },
+                Children::spawn(SpawnIter(InputAction::ALL.into_iter().map(binding_row))),
+            ),
+            widget::label("Click a binding or + then press a key or button. Escape cancels."),
+            widget::button("Reset", reset_bindings),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+fn binding_row(action: InputAction) -> impl Bundle {
+    (
+        Name::new("Binding Row"),
+        Node {
+            column_gap: px(30),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            (
+                Name::new("Action"),
+                Node {
+                    width: px(300),
+                    justify_content: JustifyContent::End,
+                    ..default()
+                },
+                children![widget::label(action.name())],
+            ),
+            (
+                Name::new("Current Bindings"),
+                Node {
+                    width: px(500),
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: px(10),
+                    row_gap: px(10),
+                    ..default()
+                },
+                BindingList(action),
+            ),
+            widget::button_small(
+                "+",
+                start_capture(BindingSlot {
+                    action,
+                    index: None
+                })
+            ),
+        ],
+    )
+}
+
+/// Holds a button for each of an action's bindings.
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+struct BindingList(InputAction);
+
+/// Shows one binding, or the prompt for a new one.
+#[derive(Component, Clone, Copy)]
+struct BindingLabel(BindingSlot);
+
+/// A button that rebinds the binding in `slot` when clicked.
+fn binding_button(slot: BindingSlot) -> impl Bundle {
+    (
+        Name::new("Binding Button"),
+        Button,
+        Node {
+            padding: UiRect::axes(px(10), px(5)),
+            ..default()
+        },
+        BorderRadius::all(px(5)),
+        BackgroundColor(BUTTON_BACKGROUND),
+        InteractionPalette {
+            none: BUTTON_BACKGROUND,
+            hovered: BUTTON_HOVERED_BACKGROUND,
+            pressed: BUTTON_PRESSED_BACKGROUND,
+        },
+        children![(
+            Name::new("Binding Button Text"),
+            Text::default(),
+            TextFont::from_font_size(24.0),
+            TextColor(BUTTON_TEXT),
+            BindingLabel(slot),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+fn start_capture(slot: BindingSlot) -> impl Fn(On<Pointer<Click>>, ResMut<BindingCapture>) {
+    move |_, mut capture| {
+        capture.0 = Some(slot);
+    }
+}
+
+fn stop_capture(mut capture: ResMut<BindingCapture>) {
+    capture.0 = None;
+}
+
+/// Give each binding its own button, plus a prompt while a new one is being
+/// added. Lists are only rebuilt when the number of buttons changes, so that
+/// focus stays put when a binding is replaced.
+fn update_binding_lists(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    capture: Res<BindingCapture>,
+    lists: Query<(Entity, &BindingList, Option<&Children>)>,
+) {
+    for (entity, list, children) in &lists {
+        let action = list.0;
+        let adding = capture.0
+            == Some(BindingSlot {
+                action,
+                index: None,
+            });
+        let count = settings.bindings.get(action).len();
+        let wanted = count + usize::from(adding);
+        if children.map_or(0, |children| children.len()) == wanted {
+            continue;
+        }
+
+        commands.entity(entity).despawn_related::<Children>();
+        for index in 0..count {
+            let slot = BindingSlot {
+                action,
+                index: Some(index),
+            };
+            commands
+                .spawn((binding_button(slot), ChildOf(entity)))
+                .observe(start_capture(slot));
+        }
+        if adding {
+            commands.spawn((
+                widget::label(CAPTURE_PROMPT),
+                BindingLabel(BindingSlot {
+                    action,
+                    index: None,
+                }),
+                ChildOf(entity),
+            ));
+        }
+    }
+}
+
+fn update_binding_labels(
+    settings: Res<Settings>,
+    capture: Res<BindingCapture>,
+    mut labels: Query<(&BindingLabel, &mut Text)>,
+) {
+    for (label, mut text) in &mut labels {
+        let slot = label.0;
+        let binding = slot
+            .index
+            .and_then(|index| settings.bindings.get(slot.action).get(index));
+        text.0 = match binding {
+            Some(binding) if capture.0 != Some(slot) => binding.name(),
+            _ => CAPTURE_PROMPT.to_string(),
+        };
+    }
+}
+
+fn reset_bindings(
+    _: On<Pointer<Click>>,
+    mut settings: ResMut<Settings>,
+    mut capture: ResMut<BindingCapture>,
+) {
+    settings.bindings = Bindings::default();
+    capture.0 = None;
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
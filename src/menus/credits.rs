@@ -1,14 +1,20 @@
 //! The credits menu.
 
-use bevy::{ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
 
-use crate::{asset_tracking::LoadResource, audio::music, menus::Menu, theme::widget};
+use crate::{
+    asset_tracking::LoadResource,
+    audio::music,
+    input::{InputAction, action_just_pressed},
+    menus::Menu,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Credits), spawn_credits_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Credits).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Credits).and(action_just_pressed(InputAction::Back))),
     );
 
     app.load_resource::<CreditsAssets>();
@@ -38,6 +44,10 @@ fn assets() -> impl Bundle {
     grid(vec![
         ["Art", "CC0 by Shade - merchant-shade.itch.io"],
         ["Button SFX", "CC0 by Jaszunio15"],
+        [
+            "Bump and win SFX",
+            "CC0, made with asset_pipeline/stinger_gen.py",
+        ],
         ["Music", "CC BY 3.0 by Kevin MacLeod"],
         [
             "Bevy logo",
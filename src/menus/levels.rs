@@ -0,0 +1,131 @@
+//! The level select menu, for playing any campaign level that has been unlocked.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_tracking::ResourceHandles,
+    gameplay::{
+        level::{Campaign, CampaignProgress, Level, LevelAssets, MazeSource},
+        stats::format_time,
+    },
+    input::{InputAction, action_just_pressed},
+    menus::Menu,
+    save::SaveData,
+    screens::{Screen, enter_gameplay},
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Levels), spawn_levels_menu);
+    // The campaign may still be loading when the menu opens.
+    app.add_systems(
+        Update,
+        spawn_levels_menu.run_if(in_state(Menu::Levels).and(resource_added::<LevelAssets>)),
+    );
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Levels).and(action_just_pressed(InputAction::Back))),
+    );
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct LevelsMenu;
+
+fn spawn_levels_menu(
+    mut commands: Commands,
+    existing_menu: Query<Entity, With<LevelsMenu>>,
+    level_assets: Option<Res<LevelAssets>>,
+    campaigns: Res<Assets<Campaign>>,
+    levels: Res<Assets<Level>>,
+    save: Res<SaveData>,
+) {
+    for entity in &existing_menu {
+        commands.entity(entity).despawn();
+    }
+
+    let mut menu = commands.spawn((
+        widget::ui_root("Levels Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Levels),
+        LevelsMenu,
+        children![widget::header("Levels")],
+    ));
+
+    let Some(level_assets) = level_assets else {
+        menu.with_child(widget::label("Loading..."));
+        menu.with_child(widget::button("Back", go_back_on_click));
+        return;
+    };
+
+    menu.with_children(|parent| {
+        let mut grid = parent.spawn((
+            Name::new("Levels Grid"),
+            Node {
+                display: Display::Grid,
+                row_gap: px(10),
+                column_gap: px(30),
+                grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ));
+        for index in 0..level_assets.campaign_length(&campaigns) {
+            let name = level_assets
+                .campaign_level(&campaigns, index)
+                .and_then(|handle| levels.get(&handle))
+                .map_or_else(
+                    || format!("Level {}", index + 1),
+                    |level| level.name.clone(),
+                );
+            let best_time = save
+                .level(&name)
+                .and_then(|record| record.best_time())
+                .map_or_else(
+                    || "-".to_string(),
+                    |time| format!("Best {}", format_time(time)),
+                );
+
+            grid.with_children(|grid| {
+                if index < save.unlocked_levels {
+                    grid.spawn(widget::button(name, play_level(index)));
+                } else {
+                    grid.spawn((
+                        widget::label(format!("{name} (locked)")),
+                        Node {
+                            justify_self: JustifySelf::Center,
+                            ..default()
+                        },
+                    ));
+                }
+                grid.spawn(widget::label(best_time));
+            });
+        }
+    });
+    menu.with_child(widget::button("Back", go_back_on_click));
+}
+
+/// An action that starts the campaign from the level at `index`.
+fn play_level(
+    index: usize,
+) -> impl Fn(
+    On<Pointer<Click>>,
+    ResMut<MazeSource>,
+    ResMut<CampaignProgress>,
+    Res<ResourceHandles>,
+    ResMut<NextState<Screen>>,
+) {
+    move |_, mut maze_source, mut campaign_progress, resource_handles, mut next_screen| {
+        *maze_source = MazeSource::Campaign;
+        campaign_progress.level = index;
+        enter_gameplay(&resource_handles, &mut next_screen);
+    }
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
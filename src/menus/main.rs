@@ -2,7 +2,13 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::ResourceHandles,
+    gameplay::{level::MazeSource, maze::seed::MazeSeed},
+    menus::Menu,
+    screens::{Screen, enter_gameplay},
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
@@ -15,30 +21,56 @@ fn spawn_main_menu(mut commands: Commands) {
         DespawnOnExit(Menu::Main),
         #[cfg(not(target_family = "wasm"))]
         children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
+            widget::button("Play", open_levels_menu),
+            widget::button("Random Maze", play_random_maze),
+            widget::button("Maze of the Day", play_maze_of_the_day),
+            widget::button("Enter Seed", open_seed_menu),
             widget::button("Settings", open_settings_menu),
             widget::button("Credits", open_credits_menu),
             widget::button("Exit", exit_app),
         ],
         #[cfg(target_family = "wasm")]
         children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
+            widget::button("Play", open_levels_menu),
+            widget::button("Random Maze", play_random_maze),
+            widget::button("Maze of the Day", play_maze_of_the_day),
+            widget::button("Enter Seed", open_seed_menu),
             widget::button("Settings", open_settings_menu),
             widget::button("Credits", open_credits_menu),
         ],
     ));
 }
 
-fn enter_loading_or_gameplay_screen(
+fn open_levels_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Levels);
+}
+
+fn play_random_maze(
+    _: On<Pointer<Click>>,
+    mut maze_source: ResMut<MazeSource>,
+    mut maze_seed: ResMut<MazeSeed>,
+    resource_handles: Res<ResourceHandles>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    *maze_source = MazeSource::Generated;
+    *maze_seed = MazeSeed::random();
+    enter_gameplay(&resource_handles, &mut next_screen);
+}
+
+fn play_maze_of_the_day(
     _: On<Pointer<Click>>,
+    mut maze_source: ResMut<MazeSource>,
+    mut maze_seed: ResMut<MazeSeed>,
     resource_handles: Res<ResourceHandles>,
     mut next_screen: ResMut<NextState<Screen>>,
 ) {
-    if resource_handles.is_all_done() {
-        next_screen.set(Screen::Gameplay);
-    } else {
-        next_screen.set(Screen::Loading);
-    }
+    *maze_source = MazeSource::Generated;
+    *maze_seed = MazeSeed::daily();
+    enter_gameplay(&resource_handles, &mut next_screen);
+}
+
+fn open_seed_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Seed);
 }
 
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
@@ -1,8 +1,11 @@
 //! The game's menus and transitions between them.
 
+mod controls;
 mod credits;
+mod levels;
 mod main;
 mod pause;
+mod seed;
 mod settings;
 
 use bevy::prelude::*;
@@ -11,10 +14,13 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Menu>();
 
     app.add_plugins((
+        controls::plugin,
         credits::plugin,
+        levels::plugin,
         main::plugin,
         settings::plugin,
         pause::plugin,
+        seed::plugin,
     ));
 }
 
@@ -24,6 +30,9 @@ pub enum Menu {
     None,
     Main,
     Credits,
+    Levels,
+    Seed,
     Settings,
+    Controls,
     Pause,
 }
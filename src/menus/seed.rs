@@ -0,0 +1,183 @@
+//! The seed menu, for replaying a maze somebody else generated.
+
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+
+use crate::{
+    asset_tracking::ResourceHandles,
+    gameplay::{level::MazeSource, maze::seed::MazeSeed},
+    input::{InputAction, action_just_pressed},
+    menus::Menu,
+    save::SaveData,
+    screens::{Screen, enter_gameplay},
+    theme::{navigation::button_focused, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Seed), (clear_seed_entry, spawn_seed_menu));
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Seed).and(action_just_pressed(InputAction::Back))),
+    );
+
+    app.init_resource::<SeedEntry>();
+    app.add_systems(
+        Update,
+        (
+            type_seed,
+            play_entered_seed
+                .run_if(action_just_pressed(InputAction::Confirm).and(not(button_focused))),
+            update_seed_entry_label,
+        )
+            .chain()
+            .run_if(in_state(Menu::Seed)),
+    );
+}
+
+/// The digits typed so far.
+#[derive(Resource, Debug, Default)]
+struct SeedEntry(String);
+
+impl SeedEntry {
+    fn seed(&self) -> Option<MazeSeed> {
+        self.0.parse().ok().map(MazeSeed::new)
+    }
+}
+
+fn clear_seed_entry(mut seed_entry: ResMut<SeedEntry>) {
+    seed_entry.0.clear();
+}
+
+fn spawn_seed_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Seed Menu"),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Seed),
+        children![
+            widget::header("Enter a seed"),
+            (widget::label(""), SeedEntryLabel),
+            widget::label("Type the digits and press Enter"),
+            widget::button("Play", play_on_click),
+            widget::button("Recent seed", recall_seed),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SeedEntryLabel;
+
+fn type_seed(mut keyboard_input: MessageReader<KeyboardInput>, mut seed_entry: ResMut<SeedEntry>) {
+    for input in keyboard_input.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        match &input.logical_key {
+            Key::Character(text) if text.chars().all(|c| c.is_ascii_digit()) => {
+                let mut entry = format!("{}{text}", seed_entry.0);
+                // Keep whatever still fits in a seed.
+                while !entry.is_empty() && entry.parse::<u64>().is_err() {
+                    entry.pop();
+                }
+                seed_entry.0 = entry;
+            }
+            Key::Backspace => {
+                seed_entry.0.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Play the typed seed on confirm, unless a button has focus and takes the
+/// confirm for itself.
+fn play_entered_seed(
+    seed_entry: Res<SeedEntry>,
+    maze_source: ResMut<MazeSource>,
+    maze_seed: ResMut<MazeSeed>,
+    resource_handles: Res<ResourceHandles>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    play(
+        &seed_entry,
+        maze_source,
+        maze_seed,
+        &resource_handles,
+        &mut next_screen,
+    );
+}
+
+fn update_seed_entry_label(
+    seed_entry: Res<SeedEntry>,
+    mut label: Single<&mut Text, With<SeedEntryLabel>>,
+) {
+    if seed_entry.is_changed() {
+        label.0 = if seed_entry.0.is_empty() {
+            "_".to_string()
+        } else {
+            seed_entry.0.clone()
+        };
+    }
+}
+
+/// Fill in a recently played seed, going further back with each click. Handy
+/// without a keyboard.
+fn recall_seed(_: On<Pointer<Click>>, save: Res<SaveData>, mut seed_entry: ResMut<SeedEntry>) {
+    let history = &save.seed_history;
+    let next = match history
+        .iter()
+        .position(|seed| seed.to_string() == seed_entry.0)
+    {
+        Some(index) => (index + 1) % history.len(),
+        None => 0,
+    };
+    if let Some(seed) = history.get(next) {
+        seed_entry.0 = seed.to_string();
+    }
+}
+
+fn play_on_click(
+    _: On<Pointer<Click>>,
+    seed_entry: Res<SeedEntry>,
+    maze_source: ResMut<MazeSource>,
+    maze_seed: ResMut<MazeSeed>,
+    resource_handles: Res<ResourceHandles>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    play(
+        &seed_entry,
+        maze_source,
+        maze_seed,
+        &resource_handles,
+        &mut next_screen,
+    );
+}
+
+fn play(
+    seed_entry: &SeedEntry,
+    mut maze_source: ResMut<MazeSource>,
+    mut maze_seed: ResMut<MazeSeed>,
+    resource_handles: &ResourceHandles,
+    next_screen: &mut NextState<Screen>,
+) {
+    let Some(seed) = seed_entry.seed() else {
+        return;
+    };
+    *maze_source = MazeSource::Generated;
+    *maze_seed = seed;
+    enter_gameplay(resource_handles, next_screen);
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
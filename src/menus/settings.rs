@@ -1,21 +1,33 @@
-//! The settings menu.
+//! The settings menu. It edits [`Settings`], which takes care of applying and
+//! saving them.
 //!
 //! Additional settings and accessibility options should go here.
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    input::{InputAction, action_just_pressed},
+    menus::Menu,
+    screens::Screen,
+    settings::Settings,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(in_state(Menu::Settings).and(action_just_pressed(InputAction::Back))),
     );
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (
+            update_volume_labels,
+            update_toggle_labels,
+            update_dead_zone_label,
+        )
+            .run_if(in_state(Menu::Settings)),
     );
 }
 
@@ -27,6 +39,7 @@ fn spawn_settings_menu(mut commands: Commands) {
         children![
             widget::header("Settings"),
             settings_grid(),
+            widget::button("Controls", open_controls_menu),
             widget::button("Back", go_back_on_click),
         ],
     ));
@@ -43,37 +56,86 @@ fn settings_grid() -> impl Bundle {
             ..default()
         },
         children![
-            (
-                widget::label("Master Volume"),
-                Node {
-                    justify_self: JustifySelf::End,
-                    ..default()
-                }
-            ),
-            global_volume_widget(),
+            setting_label("Master Volume"),
+            volume_widget(VolumeChannel::Master),
+            setting_label("Music Volume"),
+            volume_widget(VolumeChannel::Music),
+            setting_label("Sound Volume"),
+            volume_widget(VolumeChannel::SoundEffects),
+            setting_label("Mute Music"),
+            toggle_widget(Toggle::MuteMusic),
+            setting_label("Mute Sounds"),
+            toggle_widget(Toggle::MuteSoundEffects),
+            setting_label("Fullscreen"),
+            toggle_widget(Toggle::Fullscreen),
+            setting_label("Stick Dead Zone"),
+            dead_zone_widget(),
         ],
     )
 }
 
-fn global_volume_widget() -> impl Bundle {
+fn setting_label(text: impl Into<String>) -> impl Bundle {
     (
-        Name::new("Global Volume Widget"),
+        widget::label(text),
+        Node {
+            justify_self: JustifySelf::End,
+            ..default()
+        },
+    )
+}
+
+/// A setting's current value, between the buttons that change it.
+fn setting_value(marker: impl Component) -> impl Bundle {
+    (
+        Name::new("Current Value"),
+        Node {
+            padding: UiRect::horizontal(px(10)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(widget::label(""), marker)],
+    )
+}
+
+/// The volumes that can be set. Music and sound effects are scaled by the
+/// master volume as well as their own.
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+enum VolumeChannel {
+    Master,
+    Music,
+    SoundEffects,
+}
+
+impl VolumeChannel {
+    fn volume(self, settings: &Settings) -> f32 {
+        match self {
+            Self::Master => settings.master_volume,
+            Self::Music => settings.music_volume,
+            Self::SoundEffects => settings.sfx_volume,
+        }
+    }
+
+    fn volume_mut(self, settings: &mut Settings) -> &mut f32 {
+        match self {
+            Self::Master => &mut settings.master_volume,
+            Self::Music => &mut settings.music_volume,
+            Self::SoundEffects => &mut settings.sfx_volume,
+        }
+    }
+}
+
+fn volume_widget(channel: VolumeChannel) -> impl Bundle {
+    (
+        Name::new("Volume Widget"),
         Node {
             justify_self: JustifySelf::Start,
             ..default()
         },
         children![
-            widget::button_small("-", lower_global_volume),
-            (
-                Name::new("Current Volume"),
-                Node {
-                    padding: UiRect::horizontal(px(10)),
-                    justify_content: JustifyContent::Center,
-                    ..default()
-                },
-                children![(widget::label(""), GlobalVolumeLabel)],
-            ),
-            widget::button_small("+", raise_global_volume),
+            widget::button_small("-", lower_volume(channel)),
+            setting_value(channel),
+            widget::button_small("+", raise_volume(channel)),
         ],
     )
 }
@@ -81,28 +143,123 @@ fn global_volume_widget() -> impl Bundle {
 const MIN_VOLUME: f32 = 0.0;
 const MAX_VOLUME: f32 = 3.0;
 
-fn lower_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn lower_volume(channel: VolumeChannel) -> impl Fn(On<Pointer<Click>>, ResMut<Settings>) {
+    move |_, mut settings| {
+        let volume = channel.volume_mut(&mut settings);
+        *volume = (*volume - 0.1).max(MIN_VOLUME);
+    }
+}
+
+fn raise_volume(channel: VolumeChannel) -> impl Fn(On<Pointer<Click>>, ResMut<Settings>) {
+    move |_, mut settings| {
+        let volume = channel.volume_mut(&mut settings);
+        *volume = (*volume + 0.1).min(MAX_VOLUME);
+    }
 }
 
-fn raise_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() + 0.1).min(MAX_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn update_volume_labels(settings: Res<Settings>, mut labels: Query<(&VolumeChannel, &mut Text)>) {
+    for (channel, mut text) in &mut labels {
+        let percent = 100.0 * channel.volume(&settings);
+        text.0 = format!("{percent:3.0}%");
+    }
+}
+
+/// The settings that are either on or off.
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+enum Toggle {
+    MuteMusic,
+    MuteSoundEffects,
+    Fullscreen,
+}
+
+impl Toggle {
+    fn value(self, settings: &Settings) -> bool {
+        match self {
+            Self::MuteMusic => settings.music_muted,
+            Self::MuteSoundEffects => settings.sfx_muted,
+            Self::Fullscreen => settings.fullscreen,
+        }
+    }
+
+    fn value_mut(self, settings: &mut Settings) -> &mut bool {
+        match self {
+            Self::MuteMusic => &mut settings.music_muted,
+            Self::MuteSoundEffects => &mut settings.sfx_muted,
+            Self::Fullscreen => &mut settings.fullscreen,
+        }
+    }
+}
+
+fn toggle_widget(toggle: Toggle) -> impl Bundle {
+    (
+        Name::new("Toggle Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("<", flip(toggle)),
+            setting_value(toggle),
+            widget::button_small(">", flip(toggle)),
+        ],
+    )
+}
+
+fn flip(toggle: Toggle) -> impl Fn(On<Pointer<Click>>, ResMut<Settings>) {
+    move |_, mut settings| {
+        let value = toggle.value_mut(&mut settings);
+        *value = !*value;
+    }
+}
+
+fn update_toggle_labels(settings: Res<Settings>, mut labels: Query<(&Toggle, &mut Text)>) {
+    for (toggle, mut text) in &mut labels {
+        text.0 = if toggle.value(&settings) { "On" } else { "Off" }.to_string();
+    }
 }
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct GlobalVolumeLabel;
+struct DeadZoneLabel;
 
-fn update_global_volume_label(
-    global_volume: Res<GlobalVolume>,
-    mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
+fn dead_zone_widget() -> impl Bundle {
+    (
+        Name::new("Dead Zone Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_dead_zone),
+            setting_value(DeadZoneLabel),
+            widget::button_small("+", raise_dead_zone),
+        ],
+    )
+}
+
+const MAX_DEAD_ZONE: f32 = 0.9;
+
+fn lower_dead_zone(_: On<Pointer<Click>>, mut settings: ResMut<Settings>) {
+    settings.stick_dead_zone = (settings.stick_dead_zone - 0.05).max(0.0);
+}
+
+fn raise_dead_zone(_: On<Pointer<Click>>, mut settings: ResMut<Settings>) {
+    settings.stick_dead_zone = (settings.stick_dead_zone + 0.05).min(MAX_DEAD_ZONE);
+}
+
+fn update_dead_zone_label(
+    settings: Res<Settings>,
+    mut label: Single<&mut Text, With<DeadZoneLabel>>,
 ) {
-    let percent = 100.0 * global_volume.volume.to_linear();
+    let percent = 100.0 * settings.stick_dead_zone;
     label.0 = format!("{percent:3.0}%");
 }
 
+fn open_controls_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Controls);
+}
+
 fn go_back_on_click(
     _: On<Pointer<Click>>,
     screen: Res<State<Screen>>,
@@ -0,0 +1,128 @@
+//! Progress that is kept between sessions: best times, which campaign levels
+//! are completed and unlocked, and the seeds that have been played.
+//!
+//! The save file is RON with a `version` field. A file that can't be read (or
+//! was written by a newer version of the game) is set aside and a fresh save is
+//! started, rather than losing the ability to play.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{
+        level::{CampaignProgress, CurrentLevel, MazeSource},
+        maze::seed::MazeSeed,
+        stats::RunStats,
+    },
+    screens::Screen,
+    storage,
+};
+
+const SAVE_FILE: &str = "save.ron";
+/// Bump this when the format changes, and teach [`SaveData::parse`] to migrate
+/// from the old version.
+const SAVE_VERSION: u32 = 1;
+/// How many recently played seeds to remember.
+const SEED_HISTORY_LEN: usize = 20;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(storage::load(SAVE_FILE, SaveData::parse).unwrap_or_default());
+    app.add_systems(OnEnter(Screen::Gameplay), record_seed);
+    app.add_systems(OnEnter(Screen::LevelComplete), record_level_complete);
+    app.add_systems(
+        Update,
+        write_save_data.run_if(resource_changed::<SaveData>.and(not(resource_added::<SaveData>))),
+    );
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct SaveData {
+    version: u32,
+    /// Records for campaign levels, by level name.
+    pub levels: BTreeMap<String, LevelRecord>,
+    /// How many campaign levels can be played, counting from the first.
+    pub unlocked_levels: usize,
+    /// The most recently played seeds, most recent first.
+    pub seed_history: Vec<u64>,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            version: SAVE_VERSION,
+            levels: BTreeMap::new(),
+            unlocked_levels: 1,
+            seed_history: Vec::new(),
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct LevelRecord {
+    pub completed: bool,
+    /// The fastest run, in seconds.
+    pub best_time: Option<f32>,
+}
+
+impl LevelRecord {
+    pub fn best_time(&self) -> Option<Duration> {
+        self.best_time.map(Duration::from_secs_f32)
+    }
+}
+
+/// Just enough of the save file to tell which version wrote it.
+#[derive(Deserialize)]
+struct SaveVersion {
+    version: u32,
+}
+
+impl SaveData {
+    fn parse(contents: &str) -> Result<Self, String> {
+        let SaveVersion { version } = ron::from_str(contents).map_err(|error| error.to_string())?;
+        match version {
+            SAVE_VERSION => ron::from_str(contents).map_err(|error| error.to_string()),
+            _ => Err(format!("unsupported save version {version}")),
+        }
+    }
+
+    pub fn level(&self, name: &str) -> Option<&LevelRecord> {
+        self.levels.get(name)
+    }
+}
+
+fn record_seed(maze_source: Res<MazeSource>, maze_seed: Res<MazeSeed>, mut save: ResMut<SaveData>) {
+    if *maze_source != MazeSource::Generated {
+        return;
+    }
+    save.seed_history.retain(|&seed| seed != maze_seed.value);
+    save.seed_history.insert(0, maze_seed.value);
+    save.seed_history.truncate(SEED_HISTORY_LEN);
+}
+
+fn record_level_complete(
+    maze_source: Res<MazeSource>,
+    campaign_progress: Res<CampaignProgress>,
+    current_level: Res<CurrentLevel>,
+    run_stats: Res<RunStats>,
+    mut save: ResMut<SaveData>,
+) {
+    if *maze_source != MazeSource::Campaign {
+        return;
+    }
+    let time = run_stats.time.elapsed_secs();
+    let record = save.levels.entry(current_level.0.name.clone()).or_default();
+    record.completed = true;
+    if record.best_time.is_none_or(|best_time| time < best_time) {
+        record.best_time = Some(time);
+    }
+    save.unlocked_levels = save.unlocked_levels.max(campaign_progress.level + 2);
+}
+
+fn write_save_data(save: Res<SaveData>) {
+    storage::store(SAVE_FILE, &*save);
+}
@@ -1,25 +1,35 @@
 //! The screen state for the main gameplay.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
-use crate::{Pause, gameplay::spawn_environment, menus::Menu, screens::Screen};
+use crate::{
+    Pause,
+    gameplay::{level::load_level, spawn_environment},
+    input::{InputAction, action_just_pressed},
+    menus::Menu,
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Gameplay), spawn_environment);
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        spawn_environment.after(load_level),
+    );
 
     // Toggle pause on key press.
     app.add_systems(
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
-                in_state(Screen::Gameplay)
-                    .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                in_state(Screen::Gameplay).and(in_state(Menu::None)).and(
+                    action_just_pressed(InputAction::Pause)
+                        .or(action_just_pressed(InputAction::Back)),
+                ),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(action_just_pressed(InputAction::Pause)),
             ),
         ),
     );
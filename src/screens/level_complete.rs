@@ -0,0 +1,186 @@
+//! The screen between levels, showing how the run went. In the campaign it
+//! moves on to the next level by itself after a short wait.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems,
+    gameplay::{
+        level::{Campaign, CampaignProgress, CurrentLevel, LevelAssets, MazeSource},
+        stats::RunStats,
+    },
+    input::{InputAction, action_just_pressed},
+    screens::Screen,
+    theme::{navigation::button_focused, widget},
+};
+
+/// How long the screen stays up before the next campaign level starts.
+const AUTO_ADVANCE_SECS: f32 = 5.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Screen::LevelComplete),
+        (start_auto_advance, spawn_level_complete_screen).chain(),
+    );
+    app.add_systems(OnExit(Screen::LevelComplete), stop_auto_advance);
+
+    app.add_systems(
+        Update,
+        (
+            tick_auto_advance.in_set(AppSystems::TickTimers),
+            (
+                update_countdown_label,
+                next_level.run_if(auto_advance_finished.or(
+                    // A focused button takes the press instead.
+                    action_just_pressed(InputAction::Confirm).and(not(button_focused)),
+                )),
+            )
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::LevelComplete).and(resource_exists::<AutoAdvance>)),
+    );
+    app.add_systems(
+        Update,
+        quit_to_title
+            .run_if(in_state(Screen::LevelComplete).and(action_just_pressed(InputAction::Back))),
+    );
+}
+
+/// Counts down to the next campaign level. Only exists while there is one.
+#[derive(Resource, Debug)]
+struct AutoAdvance(Timer);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CountdownLabel;
+
+fn start_auto_advance(
+    mut commands: Commands,
+    maze_source: Res<MazeSource>,
+    campaign_progress: Res<CampaignProgress>,
+    level_assets: Res<LevelAssets>,
+    campaigns: Res<Assets<Campaign>>,
+) {
+    let has_next_level = *maze_source == MazeSource::Campaign
+        && campaign_progress.level + 1 < level_assets.campaign_length(&campaigns);
+    if has_next_level {
+        commands.insert_resource(AutoAdvance(Timer::from_seconds(
+            AUTO_ADVANCE_SECS,
+            TimerMode::Once,
+        )));
+    }
+}
+
+fn stop_auto_advance(mut commands: Commands) {
+    commands.remove_resource::<AutoAdvance>();
+}
+
+fn spawn_level_complete_screen(
+    mut commands: Commands,
+    maze_source: Res<MazeSource>,
+    current_level: Res<CurrentLevel>,
+    run_stats: Res<RunStats>,
+    auto_advance: Option<Res<AutoAdvance>>,
+) {
+    let header = match (*maze_source, &auto_advance) {
+        (MazeSource::Campaign, None) => "Campaign complete!",
+        (MazeSource::Campaign, Some(_)) => "Level complete!",
+        (MazeSource::Generated, _) => "Maze complete!",
+    };
+
+    let mut root = commands.spawn((
+        widget::ui_root("Level Complete Screen"),
+        DespawnOnExit(Screen::LevelComplete),
+        children![
+            widget::header(header),
+            widget::label(current_level.0.name.clone()),
+            widget::label(run_stats.time_label()),
+            widget::label(run_stats.distance_label()),
+            widget::label(run_stats.wall_bumps_label()),
+            widget::label(run_stats.hints_label()),
+        ],
+    ));
+    if run_stats.collectibles > 0 {
+        root.with_children(|parent| {
+            parent.spawn(widget::label(run_stats.collected_label()));
+            parent.spawn(widget::label(run_stats.score_label()));
+        });
+    }
+    if run_stats.caught > 0 {
+        root.with_children(|parent| {
+            parent.spawn(widget::label(run_stats.caught_label()));
+        });
+    }
+    if auto_advance.is_some() {
+        root.with_children(|parent| {
+            parent.spawn((widget::label(""), CountdownLabel));
+            parent.spawn(widget::button("Next level", next_level_on_click));
+        });
+    }
+    root.with_child(widget::button("Quit to title", quit_to_title_on_click));
+}
+
+fn tick_auto_advance(time: Res<Time>, mut auto_advance: ResMut<AutoAdvance>) {
+    auto_advance.0.tick(time.delta());
+}
+
+fn auto_advance_finished(auto_advance: Res<AutoAdvance>) -> bool {
+    auto_advance.0.is_finished()
+}
+
+fn update_countdown_label(
+    auto_advance: Res<AutoAdvance>,
+    mut label: Single<&mut Text, With<CountdownLabel>>,
+) {
+    let seconds_left = auto_advance.0.remaining_secs().ceil();
+    label.0 = format!("Next level in {seconds_left}...");
+}
+
+/// Move on to the next campaign level. The countdown, confirming and the
+/// button can all land on the same frame, so only the first one counts.
+fn next_level(
+    mut campaign_progress: ResMut<CampaignProgress>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if matches!(*next_screen, NextState::Pending(_)) {
+        return;
+    }
+    campaign_progress.level += 1;
+    next_screen.set(Screen::Gameplay);
+}
+
+fn next_level_on_click(
+    _: On<Pointer<Click>>,
+    campaign_progress: ResMut<CampaignProgress>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    next_level(campaign_progress, next_screen);
+}
+
+fn quit_to_title(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+fn quit_to_title_on_click(_: On<Pointer<Click>>, next_screen: ResMut<NextState<Screen>>) {
+    quit_to_title(next_screen);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn advancing_twice_in_a_frame_skips_no_levels() {
+        let mut world = World::new();
+        world.init_resource::<CampaignProgress>();
+        world.init_resource::<NextState<Screen>>();
+
+        world.run_system_once(next_level).unwrap();
+        world.run_system_once(next_level).unwrap();
+
+        assert_eq!(world.resource::<CampaignProgress>().level, 1);
+    }
+}
@@ -1,17 +1,21 @@
 //! The game's main screen states and transitions between them.
 
 mod gameplay;
+mod level_complete;
 mod loading;
 mod splash;
 mod title;
 
 use bevy::prelude::*;
 
+use crate::asset_tracking::ResourceHandles;
+
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
 
     app.add_plugins((
         gameplay::plugin,
+        level_complete::plugin,
         loading::plugin,
         splash::plugin,
         title::plugin,
@@ -28,4 +32,14 @@ pub enum Screen {
     Loading,
     // #[default]
     Gameplay,
+    LevelComplete,
+}
+
+/// Start gameplay, going through the loading screen if assets are still loading.
+pub fn enter_gameplay(resource_handles: &ResourceHandles, next_screen: &mut NextState<Screen>) {
+    if resource_handles.is_all_done() {
+        next_screen.set(Screen::Gameplay);
+    } else {
+        next_screen.set(Screen::Loading);
+    }
 }
@@ -2,11 +2,15 @@
 
 use bevy::{
     image::{ImageLoaderSettings, ImageSampler},
-    input::common_conditions::input_just_pressed,
     prelude::*,
 };
 
-use crate::{AppSystems, screens::Screen, theme::widget};
+use crate::{
+    AppSystems,
+    input::{InputAction, action_just_pressed},
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Spawn splash screen.
@@ -35,11 +39,11 @@ pub(super) fn plugin(app: &mut App) {
             .run_if(in_state(Screen::Splash)),
     );
 
-    // Exit the splash screen early if the player hits escape.
+    // Exit the splash screen early if the player goes back.
     app.add_systems(
         Update,
         enter_title_screen
-            .run_if(input_just_pressed(KeyCode::Escape).and(in_state(Screen::Splash))),
+            .run_if(action_just_pressed(InputAction::Back).and(in_state(Screen::Splash))),
     );
 }
 
@@ -0,0 +1,88 @@
+//! Player preferences, kept between sessions. [`Settings`] is the single source
+//! of truth: the settings menu edits it, and the rest of the game follows it.
+
+use bevy::{
+    audio::Volume,
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{input::Bindings, storage};
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(storage::load(SETTINGS_FILE, Settings::parse).unwrap_or_default());
+    app.add_systems(
+        Update,
+        (
+            apply_volume,
+            apply_display,
+            write_settings.run_if(not(resource_added::<Settings>)),
+        )
+            .run_if(resource_changed::<Settings>),
+    );
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    /// Scales all audio, linearly.
+    pub master_volume: f32,
+    /// Scales music on top of the master volume, linearly.
+    pub music_volume: f32,
+    /// Scales sound effects on top of the master volume, linearly.
+    pub sfx_volume: f32,
+    pub music_muted: bool,
+    pub sfx_muted: bool,
+    pub bindings: Bindings,
+    /// How far an analog stick must be pushed before it moves the player, as a
+    /// fraction of its full range.
+    pub stick_dead_zone: f32,
+    pub fullscreen: bool,
+    /// Whether the minimap is drawn during gameplay.
+    pub show_minimap: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            music_muted: false,
+            sfx_muted: false,
+            bindings: Bindings::default(),
+            stick_dead_zone: 0.2,
+            fullscreen: false,
+            show_minimap: true,
+        }
+    }
+}
+
+impl Settings {
+    fn parse(contents: &str) -> Result<Self, String> {
+        ron::from_str(contents).map_err(|error| error.to_string())
+    }
+}
+
+fn apply_volume(settings: Res<Settings>, mut global_volume: ResMut<GlobalVolume>) {
+    global_volume.volume = Volume::Linear(settings.master_volume);
+}
+
+fn apply_display(settings: Res<Settings>, mut window: Single<&mut Window, With<PrimaryWindow>>) {
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+}
+
+fn write_settings(settings: Res<Settings>) {
+    storage::store(SETTINGS_FILE, &*settings);
+}
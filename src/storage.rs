@@ -0,0 +1,110 @@
+//! Small text files that outlive the game, like the save file. Natively they
+//! live in the platform's config directory; on the web they are kept in
+//! `localStorage`, keyed by file name.
+
+use std::io;
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+/// Read a stored file, or `None` if it has never been written.
+fn read(name: &str) -> io::Result<Option<String>> {
+    platform::read(name)
+}
+
+/// Write a stored file, replacing whatever was there.
+fn write(name: &str, contents: &str) -> io::Result<()> {
+    platform::write(name, contents)
+}
+
+/// Read and parse a stored file. A file that can't be parsed is set aside as
+/// `<name>.bad` so that starting afresh doesn't lose it for good.
+pub fn load<T>(name: &str, parse: impl FnOnce(&str) -> Result<T, String>) -> Option<T> {
+    let contents = match read(name) {
+        Ok(contents) => contents?,
+        Err(error) => {
+            warn!("Could not read {name}: {error}");
+            return None;
+        }
+    };
+    match parse(&contents) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("Could not load {name}, starting afresh: {error}");
+            if let Err(error) = write(&format!("{name}.bad"), &contents) {
+                warn!("Could not set {name} aside: {error}");
+            }
+            None
+        }
+    }
+}
+
+/// Write a value to a stored file as RON.
+pub fn store(name: &str, value: &impl Serialize) {
+    let result = ron::ser::to_string_pretty(value, default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| write(name, &contents).map_err(|error| error.to_string()));
+    if let Err(error) = result {
+        warn!("Could not write {name}: {error}");
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod platform {
+    use std::{fs, io, path::PathBuf};
+
+    use directories::ProjectDirs;
+
+    fn dir() -> io::Result<PathBuf> {
+        ProjectDirs::from("", "", "BevyMaze")
+            .map(|dirs| dirs.config_dir().to_path_buf())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))
+    }
+
+    pub fn read(name: &str) -> io::Result<Option<String>> {
+        match fs::read_to_string(dir()?.join(name)) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn write(name: &str, contents: &str) -> io::Result<()> {
+        let dir = dir()?;
+        fs::create_dir_all(&dir)?;
+        // Write to the side and rename, so a crash halfway through can't
+        // leave a truncated file behind.
+        let temporary = dir.join(format!("{name}.tmp"));
+        fs::write(&temporary, contents)?;
+        fs::rename(temporary, dir.join(name))
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod platform {
+    use std::io;
+
+    use web_sys::Storage;
+
+    fn storage() -> io::Result<Storage> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "no localStorage"))
+    }
+
+    fn key(name: &str) -> String {
+        format!("BevyMaze/{name}")
+    }
+
+    pub fn read(name: &str) -> io::Result<Option<String>> {
+        storage()?
+            .get_item(&key(name))
+            .map_err(|error| io::Error::other(format!("{error:?}")))
+    }
+
+    pub fn write(name: &str, contents: &str) -> io::Result<()> {
+        storage()?
+            .set_item(&key(name), contents)
+            .map_err(|error| io::Error::other(format!("{error:?}")))
+    }
+}
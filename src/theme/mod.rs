@@ -4,11 +4,12 @@
 #![allow(dead_code)]
 
 pub mod interaction;
+pub mod navigation;
 pub mod palette;
 pub mod widget;
 
 use bevy::app::App;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((interaction::plugin, navigation::plugin));
 }
@@ -0,0 +1,154 @@
+//! Menu navigation without a mouse. One button at a time has focus and is
+//! outlined: the move actions or a gamepad's left stick move focus to the
+//! nearest button in that direction, and confirming clicks it.
+
+use std::time::Duration;
+
+use bevy::{
+    camera::NormalizedRenderTarget,
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerButton, PointerId},
+    },
+    prelude::*,
+    window::{PrimaryWindow, WindowRef},
+};
+
+use crate::{
+    input::{Actions, InputAction},
+    theme::palette::BUTTON_FOCUS_OUTLINE,
+};
+
+/// How far the left stick must be pushed to move focus.
+const STICK_THRESHOLD: f32 = 0.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Focus>();
+    app.add_systems(Update, (move_focus, click_focused).chain());
+}
+
+/// A run condition that is true while a button has focus, and so takes
+/// [`InputAction::Confirm`] for itself.
+pub fn button_focused(focus: Res<Focus>) -> bool {
+    focus.0.is_some()
+}
+
+/// The button that gamepad input acts on, if any.
+#[derive(Resource, Debug, Default)]
+pub struct Focus(Option<Entity>);
+
+fn move_focus(
+    mut commands: Commands,
+    actions: Actions,
+    gamepads: Query<&Gamepad>,
+    buttons: Query<(Entity, &UiGlobalTransform, &InheritedVisibility), With<Button>>,
+    mut focus: ResMut<Focus>,
+    mut stick_was_pushed: Local<bool>,
+) {
+    // Forget focus when its button goes away, such as when the menu changes.
+    if focus.0.is_some_and(|entity| !buttons.contains(entity)) {
+        focus.0 = None;
+    }
+
+    // Directions are in UI space, where Y points down.
+    let mut direction = None;
+    for (action, action_direction) in [
+        (InputAction::MoveUp, Vec2::NEG_Y),
+        (InputAction::MoveDown, Vec2::Y),
+        (InputAction::MoveLeft, Vec2::NEG_X),
+        (InputAction::MoveRight, Vec2::X),
+    ] {
+        if actions.just_pressed(action) {
+            direction = Some(action_direction);
+        }
+    }
+    let mut stick_pushed = false;
+    for gamepad in &gamepads {
+        let stick = gamepad.left_stick();
+        if stick.length() > STICK_THRESHOLD {
+            stick_pushed = true;
+            // Only move once per push, along the stronger axis.
+            if !*stick_was_pushed {
+                direction = Some(if stick.x.abs() > stick.y.abs() {
+                    Vec2::new(stick.x.signum(), 0.0)
+                } else {
+                    Vec2::new(0.0, -stick.y.signum())
+                });
+            }
+        }
+    }
+    *stick_was_pushed = stick_pushed;
+    let Some(direction) = direction else {
+        return;
+    };
+
+    let candidates = buttons
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .map(|(entity, transform, _)| (entity, transform.translation));
+    let next = match focus.0.and_then(|entity| buttons.get(entity).ok()) {
+        // The first press focuses the top-left button rather than moving.
+        None => candidates
+            .min_by(|(_, a), (_, b)| (a.y, a.x).partial_cmp(&(b.y, b.x)).unwrap())
+            .map(|(entity, _)| entity),
+        Some((_, transform, _)) => {
+            let from = transform.translation;
+            // Prefer buttons straight ahead over nearer ones off to the side.
+            candidates
+                .filter_map(|(entity, position)| {
+                    let offset = position - from;
+                    let along = offset.dot(direction);
+                    let across = offset.perp_dot(direction).abs();
+                    (along > 1.0).then_some((entity, along + 2.0 * across))
+                })
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(entity, _)| entity)
+        }
+    };
+    let Some(next) = next else {
+        return;
+    };
+
+    if let Some(previous) = focus.0 {
+        commands.entity(previous).remove::<Outline>();
+    }
+    commands
+        .entity(next)
+        .insert(Outline::new(px(3), px(2), BUTTON_FOCUS_OUTLINE));
+    focus.0 = Some(next);
+}
+
+/// Click the focused button as if with the mouse, so buttons need only one
+/// action for both.
+fn click_focused(
+    mut commands: Commands,
+    actions: Actions,
+    focus: Res<Focus>,
+    buttons: Query<(&UiGlobalTransform, &ComputedNode), With<Button>>,
+    camera: Single<Entity, With<Camera2d>>,
+    window: Single<Entity, With<PrimaryWindow>>,
+) {
+    if !actions.just_pressed(InputAction::Confirm) {
+        return;
+    }
+    let Some(entity) = focus.0 else {
+        return;
+    };
+    let Ok((transform, node)) = buttons.get(entity) else {
+        return;
+    };
+    let Some(window) = WindowRef::Entity(*window).normalize(None) else {
+        return;
+    };
+
+    let location = Location {
+        target: NormalizedRenderTarget::Window(window),
+        position: transform.translation * node.inverse_scale_factor(),
+    };
+    let click = Click {
+        button: PointerButton::Primary,
+        hit: HitData::new(*camera, 0.0, None, None),
+        duration: Duration::ZERO,
+    };
+    commands.trigger(Pointer::new(PointerId::Mouse, location, click, entity));
+}
@@ -48,6 +48,27 @@ pub fn label(text: impl Into<String>) -> impl Bundle {
     )
 }
 
+/// A panel along the bottom of the screen for running text such as dialogue.
+/// Add its content as children.
+pub fn text_box(name: impl Into<Cow<'static, str>>) -> impl Bundle {
+    (
+        Name::new(name),
+        Node {
+            position_type: PositionType::Absolute,
+            left: percent(10),
+            right: percent(10),
+            bottom: px(20),
+            min_height: px(140),
+            padding: UiRect::all(px(20)),
+            flex_direction: FlexDirection::Column,
+            row_gap: px(10),
+            ..default()
+        },
+        BackgroundColor(TEXT_BOX_BACKGROUND),
+        BorderRadius::all(px(10)),
+    )
+}
+
 /// A large rounded button with text and an action defined as an [`Observer`].
 pub fn button<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
 where
@@ -3,16 +3,20 @@ pub mod tile_mesh;
 pub struct Layers {
     pub ground: f32,
     pub interactive: f32,
+    pub enemy: f32,
+    pub fog: f32,
     pub player: f32,
     // pub dialogue: f32,
-    // pub overlay: f32,
+    pub overlay: f32,
 }
 
 // A central place to track how graphics are layered.
 pub const Z: Layers = Layers {
     ground: 0.0,
     interactive: 1.0,
+    enemy: 1.5,
+    fog: 2.0,
     player: 5.0,
     // dialogue: 10.0
-    // overlay: 50.0,
+    overlay: 50.0,
 };